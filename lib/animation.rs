@@ -0,0 +1,367 @@
+use crate::transition::Easing;
+
+use cat_engine::Colour;
+
+/// Линейная интерполяция между значениями.
+pub trait Lerp:Copy{
+    fn lerp(self,to:Self,t:f32)->Self;
+}
+
+impl Lerp for f32{
+    fn lerp(self,to:f32,t:f32)->f32{
+        self+(to-self)*t
+    }
+}
+
+impl Lerp for [f32;2]{
+    fn lerp(self,to:[f32;2],t:f32)->[f32;2]{
+        [
+            self[0].lerp(to[0],t),
+            self[1].lerp(to[1],t),
+        ]
+    }
+}
+
+impl Lerp for Colour{
+    fn lerp(self,to:Colour,t:f32)->Colour{
+        [
+            self[0].lerp(to[0],t),
+            self[1].lerp(to[1],t),
+            self[2].lerp(to[2],t),
+            self[3].lerp(to[3],t),
+        ]
+    }
+}
+
+/// Повторение анимации.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum Repeat{
+    /// Один раз, затем остановка на последнем значении
+    Once,
+    /// Сначала после окончания
+    Loop,
+    /// Вперёд и обратно
+    PingPong,
+}
+
+impl Repeat{
+    /// Время внутри анимации продолжительностью `duration`
+    /// после `time` секунд проигрывания.
+    pub fn local_time(self,time:f32,duration:f32)->f32{
+        if duration<=0f32{
+            return duration
+        }
+
+        match self{
+            Repeat::Once=>time.min(duration),
+            Repeat::Loop=>time%duration,
+            Repeat::PingPong=>{
+                let time=time%(2f32*duration);
+                if time>duration{
+                    2f32*duration-time
+                }
+                else{
+                    time
+                }
+            }
+        }
+    }
+
+    pub fn finished(self,time:f32,duration:f32)->bool{
+        match self{
+            Repeat::Once=>time>=duration,
+            Repeat::Loop | Repeat::PingPong=>false,
+        }
+    }
+}
+
+/// Ключевой кадр - значение, достигаемое за `duration` секунд
+/// после предыдущего.
+#[derive(Clone,Copy,Debug)]
+pub struct Keyframe<T>{
+    pub value:T,
+    pub duration:f32,
+    pub easing:Easing,
+}
+
+/// Последовательность изменений значения.
+///
+/// ```text
+/// // Появление: прозрачность от 0 до 1 за 0.3 секунды,
+/// // 2 секунды на экране, исчезновение за 0.3 секунды
+/// let alpha=Tween::new(0f32).to(1f32,0.3f32).wait(2f32).to(0f32,0.3f32);
+/// ```
+#[derive(Clone,Debug)]
+pub struct Tween<T:Lerp>{
+    start:T,
+    keyframes:Vec<Keyframe<T>>,
+    repeat:Repeat,
+    duration:f32,
+    time:f32,
+}
+
+impl<T:Lerp> Tween<T>{
+    /// Анимация, начинающаяся со значения `start`.
+    pub fn new(start:T)->Tween<T>{
+        Self{
+            start,
+            keyframes:Vec::new(),
+            repeat:Repeat::Once,
+            duration:0f32,
+            time:0f32,
+        }
+    }
+
+    /// Линейное изменение до значения за `duration` секунд.
+    pub fn to(self,value:T,duration:f32)->Tween<T>{
+        self.to_eased(value,duration,Easing::Linear)
+    }
+
+    /// Сглаженное изменение до значения за `duration` секунд.
+    pub fn to_eased(mut self,value:T,duration:f32,easing:Easing)->Tween<T>{
+        let duration=duration.max(0f32);
+        self.keyframes.push(Keyframe{value,duration,easing});
+        self.duration+=duration;
+        self
+    }
+
+    /// Задержка на текущем значении.
+    pub fn wait(self,duration:f32)->Tween<T>{
+        let value=self.end();
+        self.to(value,duration)
+    }
+
+    pub fn repeat(mut self,repeat:Repeat)->Tween<T>{
+        self.repeat=repeat;
+        self
+    }
+
+    /// Конечное значение.
+    pub fn end(&self)->T{
+        match self.keyframes.last(){
+            Some(keyframe)=>keyframe.value,
+            None=>self.start,
+        }
+    }
+
+    /// Продолжительность одного проигрывания в секундах.
+    pub fn duration(&self)->f32{
+        self.duration
+    }
+
+    pub fn finished(&self)->bool{
+        self.repeat.finished(self.time,self.duration)
+    }
+
+    /// Возвращение в начало.
+    pub fn reset(&mut self){
+        self.time=0f32;
+    }
+
+    /// Продвижение на `elapsed` секунд.
+    pub fn update(&mut self,elapsed:f32)->T{
+        self.time+=elapsed;
+        if let Repeat::Once=self.repeat{
+            self.time=self.time.min(self.duration);
+        }
+        self.value()
+    }
+
+    /// Текущее значение.
+    pub fn value(&self)->T{
+        self.value_at(self.repeat.local_time(self.time,self.duration))
+    }
+
+    /// Значение на время `time` от начала одного проигрывания.
+    pub fn value_at(&self,time:f32)->T{
+        let mut from=self.start;
+        let mut start=0f32;
+
+        for keyframe in &self.keyframes{
+            let end=start+keyframe.duration;
+            if time<end{
+                let t=(time-start)/keyframe.duration;
+                return from.lerp(keyframe.value,keyframe.easing.apply(t))
+            }
+            from=keyframe.value;
+            start=end;
+        }
+
+        from
+    }
+}
+
+/// Текстурные координаты кадра `index` из `count` кадров,
+/// расположенных в картинке друг под другом.
+pub fn vertical_frame(index:usize,count:usize)->[f32;4]{
+    let count=count as f32;
+    let index=index as f32;
+    [
+        0f32,
+        index/count,
+        1f32,
+        (index+1f32)/count,
+    ]
+}
+
+/// Кадр анимации из набора картинок.
+#[derive(Clone,Copy,Debug)]
+pub struct SpriteFrame{
+    /// Текстурные координаты [x1,y1,x2,y2]
+    pub uv:[f32;4],
+    /// Продолжительность в секундах
+    pub duration:f32,
+}
+
+/// Покадровая анимация по текстурным координатам.
+#[derive(Clone,Debug)]
+pub struct SpriteAnimation{
+    frames:Vec<SpriteFrame>,
+    repeat:Repeat,
+    duration:f32,
+    time:f32,
+    frame:usize,
+}
+
+impl SpriteAnimation{
+    /// Анимация с первым кадром продолжительностью `duration` секунд
+    /// (анимация без кадров невозможна).
+    pub fn new(uv:[f32;4],duration:f32)->SpriteAnimation{
+        let duration=duration.max(0f32);
+        Self{
+            frames:vec![SpriteFrame{uv,duration}],
+            repeat:Repeat::Loop,
+            duration,
+            time:0f32,
+            frame:0usize,
+        }
+    }
+
+    /// Добавление кадра продолжительностью `duration` секунд.
+    pub fn frame(mut self,uv:[f32;4],duration:f32)->SpriteAnimation{
+        let duration=duration.max(0f32);
+        self.frames.push(SpriteFrame{uv,duration});
+        self.duration+=duration;
+        self
+    }
+
+    pub fn repeat(mut self,repeat:Repeat)->SpriteAnimation{
+        self.repeat=repeat;
+        self
+    }
+
+    /// Номер текущего кадра.
+    pub fn current(&self)->usize{
+        self.frame
+    }
+
+    /// Текстурные координаты текущего кадра.
+    pub fn uv(&self)->[f32;4]{
+        self.frames[self.frame].uv
+    }
+
+    pub fn finished(&self)->bool{
+        self.repeat.finished(self.time,self.duration)
+    }
+
+    /// Продвижение на `elapsed` секунд.
+    /// Возвращает `true`, если кадр сменился.
+    pub fn update(&mut self,elapsed:f32)->bool{
+        self.time+=elapsed;
+        if let Repeat::Once=self.repeat{
+            self.time=self.time.min(self.duration);
+        }
+
+        let time=self.repeat.local_time(self.time,self.duration);
+
+        let mut start=0f32;
+        let mut frame=self.frames.len().saturating_sub(1);
+        for (index,sprite_frame) in self.frames.iter().enumerate(){
+            start+=sprite_frame.duration;
+            if time<start{
+                frame=index;
+                break
+            }
+        }
+
+        let changed=frame!=self.frame;
+        self.frame=frame;
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    fn close(a:f32,b:f32)->bool{
+        (a-b).abs()<0.0001f32
+    }
+
+    #[test]
+    fn repeat_wraps_time(){
+        assert_eq!(Repeat::Once.local_time(3f32,2f32),2f32);
+        assert!(close(Repeat::Loop.local_time(2.5f32,2f32),0.5f32));
+        assert!(close(Repeat::PingPong.local_time(1.5f32,2f32),1.5f32));
+        // Обратный ход и новый круг
+        assert!(close(Repeat::PingPong.local_time(2.5f32,2f32),1.5f32));
+        assert!(close(Repeat::PingPong.local_time(4.5f32,2f32),0.5f32));
+
+        assert!(Repeat::Once.finished(2f32,2f32));
+        assert!(!Repeat::Loop.finished(10f32,2f32));
+    }
+
+    #[test]
+    fn tween_keyframes(){
+        let mut tween=Tween::new(0f32).to(1f32,1f32).wait(1f32).to(0f32,2f32);
+        assert_eq!(tween.duration(),4f32);
+        assert_eq!(tween.end(),0f32);
+
+        assert!(close(tween.update(0.5f32),0.5f32));
+        assert!(close(tween.update(1f32),1f32));
+        assert!(close(tween.update(1.5f32),0.5f32));
+        assert!(!tween.finished());
+
+        // Один проход - остановка на последнем значении
+        assert!(close(tween.update(10f32),0f32));
+        assert!(tween.finished());
+
+        tween.reset();
+        assert_eq!(tween.value(),0f32);
+    }
+
+    #[test]
+    fn tween_loop_and_ping_pong(){
+        let mut tween=Tween::new(0f32).to(2f32,2f32).repeat(Repeat::Loop);
+        assert!(close(tween.update(2.5f32),0.5f32));
+        assert!(!tween.finished());
+
+        let mut tween=Tween::new([0f32,0f32]).to([2f32,4f32],2f32).repeat(Repeat::PingPong);
+        let [x,y]=tween.update(3f32);
+        assert!(close(x,1f32) && close(y,2f32));
+        let [x,_]=tween.update(1.5f32);
+        assert!(close(x,0.5f32));
+    }
+
+    #[test]
+    fn sprite_frames_switch(){
+        let first=[0f32,0f32,1f32,0.5f32];
+        let second=[0f32,0.5f32,1f32,1f32];
+        let mut animation=SpriteAnimation::new(first,0.5f32).frame(second,0.25f32);
+
+        assert_eq!(animation.uv(),first);
+        assert!(!animation.update(0.25f32));
+        assert!(animation.update(0.3f32));
+        assert_eq!(animation.current(),1usize);
+        assert_eq!(animation.uv(),second);
+
+        // Новый круг
+        assert!(animation.update(0.25f32));
+        assert_eq!(animation.uv(),first);
+
+        let mut animation=SpriteAnimation::new(first,0.5f32).frame(second,0.25f32).repeat(Repeat::Once);
+        animation.update(5f32);
+        assert_eq!(animation.current(),1usize);
+        assert!(animation.finished());
+    }
+}
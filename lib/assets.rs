@@ -0,0 +1,523 @@
+use crate::{
+    error::{GameError,GameResult},
+    log,
+    image_cache::ScaledImageCache,
+    manifest::ResourceManifest,
+    page_table::{PageTable,dialogue_characters},
+};
+
+use cat_engine::image::{
+    self,
+    RgbaImage,
+    DynamicImage,
+    imageops::FilterType,
+};
+
+use std::{
+    collections::HashMap,
+    fs::read_to_string,
+    panic,
+    path::{Path,PathBuf},
+    sync::{
+        Arc,
+        Mutex,
+        mpsc::{channel,Sender,Receiver},
+    },
+    thread::{spawn,JoinHandle},
+};
+
+/// Загрузка изображения с изменением размера до `size`.
+pub fn load_image<P:AsRef<Path>>(path:P,size:Option<[u32;2]>)->GameResult<RgbaImage>{
+    let path=path.as_ref();
+    let mut image=image::open(path).map_err(|e|GameError::file(path,e))?;
+
+    if let Some([width,height])=size{
+        image=image.resize_exact(width,height,FilterType::Gaussian);
+    }
+
+    if let DynamicImage::ImageRgba8(image)=image{
+        Ok(image)
+    }
+    else{
+        Ok(image.into_rgba8())
+    }
+}
+
+/// Раздел манифеста с изображением.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+pub enum AssetKind{
+    Wallpaper,
+    Character,
+    Image,
+}
+
+/// Изображение в кэше: логическое имя и размер после декодирования.
+#[derive(Clone,Debug,PartialEq,Eq,Hash)]
+pub struct AssetKey{
+    pub kind:AssetKind,
+    pub name:String,
+    /// Размер, до которого изменяется изображение (`None` - исходный)
+    pub size:Option<[u32;2]>,
+}
+
+impl AssetKey{
+    pub fn wallpaper<S:Into<String>>(name:S,size:[u32;2])->AssetKey{
+        Self{
+            kind:AssetKind::Wallpaper,
+            name:name.into(),
+            size:Some(size),
+        }
+    }
+
+    pub fn character<S:Into<String>>(name:S)->AssetKey{
+        Self{
+            kind:AssetKind::Character,
+            name:name.into(),
+            size:None,
+        }
+    }
+
+    pub fn image<S:Into<String>>(name:S)->AssetKey{
+        Self{
+            kind:AssetKind::Image,
+            name:name.into(),
+            size:None,
+        }
+    }
+
+    fn path<'a>(&self,manifest:&'a ResourceManifest)->GameResult<&'a Path>{
+        match self.kind{
+            AssetKind::Wallpaper=>manifest.require_wallpaper(&self.name),
+            AssetKind::Character=>manifest.character(&self.name).ok_or_else(||GameError::missing("characters",&self.name)),
+            AssetKind::Image=>manifest.require_image(&self.name),
+        }
+    }
+}
+
+/// Настройки кэша изображений.
+pub struct AssetCacheSettings{
+    /// Количество потоков декодирования
+    workers:usize,
+    /// Предел памяти декодированных изображений в байтах
+    memory_limit:usize,
+    /// Дисковый кэш изображений с изменённым размером
+    scaled_cache:Option<ScaledImageCache>,
+}
+
+impl AssetCacheSettings{
+    pub const fn new()->AssetCacheSettings{
+        Self{
+            workers:2usize,
+            memory_limit:256usize*1024usize*1024usize,
+            scaled_cache:None,
+        }
+    }
+
+    pub fn workers(mut self,workers:usize)->AssetCacheSettings{
+        self.workers=workers.max(1usize);
+        self
+    }
+
+    pub fn memory_limit(mut self,bytes:usize)->AssetCacheSettings{
+        self.memory_limit=bytes;
+        self
+    }
+
+    /// Хранение изображений с изменённым размером (обоев) в папке `directory`.
+    pub fn scaled_cache<P:Into<PathBuf>>(mut self,directory:P)->AssetCacheSettings{
+        self.scaled_cache=Some(ScaledImageCache::new(directory));
+        self
+    }
+}
+
+enum State{
+    /// Декодируется в потоке
+    Pending,
+    Ready(Arc<RgbaImage>),
+    Failed(GameError),
+}
+
+struct Entry{
+    state:State,
+    /// Время последнего обращения (номер обращения)
+    last_used:u64,
+}
+
+/// Задача для потоков загрузки.
+enum Job{
+    /// Декодирование изображения
+    Image(AssetKey,PathBuf),
+    /// Поиск персонажей в диалоге
+    Dialogue(PathBuf),
+}
+
+/// Результат задачи.
+enum Done{
+    Image(AssetKey,GameResult<RgbaImage>),
+    /// Имена персонажей диалога
+    Dialogue(GameResult<Vec<String>>),
+}
+
+/// Кэш изображений по логическому имени.
+///
+/// Изображения декодируются в отдельных потоках по запросу
+/// (`request`, `prefetch_pages`) и забираются через `get` или `wait`.
+/// Если декодированные изображения занимают больше предела памяти,
+/// удаляются давно не использованные.
+pub struct AssetCache{
+    manifest:ResourceManifest,
+    jobs:Option<Sender<Job>>,
+    results:Receiver<Done>,
+    workers:Vec<JoinHandle<()>>,
+    entries:HashMap<AssetKey,Entry>,
+    /// Память декодированных изображений в байтах
+    memory:usize,
+    memory_limit:usize,
+    /// Счётчик обращений для порядка использования
+    tick:u64,
+}
+
+impl AssetCache{
+    pub fn new(manifest:ResourceManifest,settings:AssetCacheSettings)->AssetCache{
+        let (jobs,job_receiver)=channel::<Job>();
+        let job_receiver=Arc::new(Mutex::new(job_receiver));
+        let (result_sender,results)=channel();
+
+        let workers=(0..settings.workers).map(|_|{
+            let jobs=job_receiver.clone();
+            let results=result_sender.clone();
+            let scaled_cache=settings.scaled_cache.clone();
+            spawn(move||loop{
+                let job=match jobs.lock(){
+                    Ok(jobs)=>jobs.recv(),
+                    Err(_)=>return,
+                };
+                let done=match job{
+                    Ok(Job::Image(key,path))=>{
+                        // Паника при декодировании не должна оставить запрос без ответа
+                        let image=panic::catch_unwind(||match (&scaled_cache,key.size){
+                            (Some(scaled_cache),Some(size))=>scaled_cache.load(&path,size),
+                            _=>load_image(&path,key.size),
+                        })
+                                .unwrap_or_else(|_|Err(GameError::failed(&key.name,"сбой при декодировании")));
+                        Done::Image(key,image)
+                    }
+                    Ok(Job::Dialogue(path))=>{
+                        let characters=read_to_string(&path)
+                                .map(|text|dialogue_characters(&text))
+                                .map_err(|e|GameError::file(&path,e));
+                        Done::Dialogue(characters)
+                    }
+                    // Кэш удалён
+                    Err(_)=>return,
+                };
+
+                if results.send(done).is_err(){
+                    return
+                }
+            })
+        }).collect();
+
+        Self{
+            manifest,
+            jobs:Some(jobs),
+            results,
+            workers,
+            entries:HashMap::new(),
+            memory:0usize,
+            memory_limit:settings.memory_limit,
+            tick:0u64,
+        }
+    }
+
+    /// Постановка изображения в очередь декодирования, если его ещё нет в кэше.
+    pub fn request(&mut self,key:&AssetKey){
+        self.tick+=1;
+        if let Some(entry)=self.entries.get_mut(key){
+            entry.last_used=self.tick;
+            return
+        }
+
+        let state=match key.path(&self.manifest){
+            Ok(path)=>{
+                log::debug(format!("декодирование '{}'",key.name));
+                if self.send(Job::Image(key.clone(),path.to_path_buf())){
+                    State::Pending
+                }
+                else{
+                    State::Failed(GameError::failed(&key.name,"потоки декодирования остановлены"))
+                }
+            }
+            Err(error)=>State::Failed(error),
+        };
+
+        self.entries.insert(key.clone(),Entry{
+            state,
+            last_used:self.tick,
+        });
+    }
+
+    /// Изображение, если оно уже декодировано.
+    ///
+    /// Если изображения нет в кэше, оно ставится в очередь.
+    pub fn get(&mut self,key:&AssetKey)->Option<Arc<RgbaImage>>{
+        self.poll();
+        self.request(key);
+
+        match &self.entries[key].state{
+            State::Ready(image)=>Some(image.clone()),
+            _=>None,
+        }
+    }
+
+    /// Ожидание декодирования изображения.
+    ///
+    /// После ошибки изображение удаляется из кэша,
+    /// чтобы следующий запрос загрузил его заново.
+    pub fn wait(&mut self,key:&AssetKey)->GameResult<Arc<RgbaImage>>{
+        self.poll();
+        self.request(key);
+
+        loop{
+            match &self.entries[key].state{
+                State::Ready(image)=>return Ok(image.clone()),
+                State::Failed(_)=>{
+                    if let Some(Entry{state:State::Failed(error),..})=self.entries.remove(key){
+                        return Err(error)
+                    }
+                }
+                State::Pending=>match self.results.recv(){
+                    Ok(done)=>self.finish(done),
+                    Err(_)=>return Err(GameError::failed(&key.name,"потоки декодирования остановлены")),
+                }
+            }
+        }
+    }
+
+    /// Предзагрузка обоев и персонажей страниц `first..first+count`.
+    ///
+    /// `dialogue_path` - путь к диалогу страницы по имени диалога.
+    /// Диалоги читаются в потоках загрузки, персонажи запрашиваются при `poll`.
+    pub fn prefetch_pages<F:Fn(&str)->PathBuf>(
+        &mut self,
+        table:&PageTable,
+        first:usize,
+        count:usize,
+        wallpaper_size:[u32;2],
+        dialogue_path:F
+    ){
+        for page in table.pages().iter().skip(first).take(count){
+            self.request(&AssetKey::wallpaper(page.wallpaper.as_str(),wallpaper_size));
+
+            if !self.send(Job::Dialogue(dialogue_path(&page.dialogue))){
+                log::warning(format!("диалог '{}' не прочитан: потоки загрузки остановлены",page.dialogue));
+            }
+        }
+    }
+
+    /// Приём декодированных изображений.
+    pub fn poll(&mut self){
+        while let Ok(done)=self.results.try_recv(){
+            self.finish(done);
+        }
+    }
+
+    /// Память декодированных изображений в байтах.
+    pub fn memory(&self)->usize{
+        self.memory
+    }
+
+    /// Отправка задачи потокам. Возвращает `false`, если потоки остановлены.
+    fn send(&self,job:Job)->bool{
+        match &self.jobs{
+            Some(jobs)=>jobs.send(job).is_ok(),
+            None=>false,
+        }
+    }
+
+    fn finish(&mut self,done:Done){
+        match done{
+            Done::Image(key,image)=>self.finish_image(key,image),
+            Done::Dialogue(Ok(characters))=>for character in characters{
+                self.request(&AssetKey::character(character));
+            }
+            Done::Dialogue(Err(error))=>log::warning(format!("диалог не прочитан: {}",error)),
+        }
+    }
+
+    fn finish_image(&mut self,key:AssetKey,image:GameResult<RgbaImage>){
+        let state=match image{
+            Ok(image)=>{
+                self.memory+=image_memory(&image);
+                State::Ready(Arc::new(image))
+            }
+            Err(error)=>{
+                log::warning(format!("изображение '{}' не загружено: {}",key.name,error));
+                State::Failed(error)
+            }
+        };
+
+        let last_used=self.entries.get(&key).map_or(self.tick,|entry|entry.last_used);
+        self.entries.insert(key.clone(),Entry{
+            state,
+            last_used,
+        });
+
+        self.evict(&key);
+    }
+
+    /// Удаление давно не использованных изображений сверх предела памяти
+    /// (кроме только что декодированного `keep`).
+    fn evict(&mut self,keep:&AssetKey){
+        while self.memory>self.memory_limit{
+            let oldest=self.entries.iter()
+                    .filter(|(key,entry)|*key!=keep && matches!(entry.state,State::Ready(_)))
+                    .min_by_key(|(_,entry)|entry.last_used)
+                    .map(|(key,_)|key.clone());
+
+            let oldest=match oldest{
+                Some(key)=>key,
+                None=>break,
+            };
+
+            if let Some(Entry{state:State::Ready(image),..})=self.entries.remove(&oldest){
+                self.memory-=image_memory(&image);
+                log::debug(format!("изображение '{}' удалено из кэша",oldest.name));
+            }
+        }
+    }
+}
+
+impl Drop for AssetCache{
+    fn drop(&mut self){
+        // Закрытие очереди останавливает потоки
+        self.jobs=None;
+        for worker in self.workers.drain(..){
+            let _=worker.join();
+        }
+    }
+}
+
+fn image_memory(image:&RgbaImage)->usize{
+    image.as_raw().len()
+}
+
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    use crate::resources::ResourceResolver;
+
+    use std::{
+        env::temp_dir,
+        fs::{create_dir_all,remove_dir_all,remove_file},
+    };
+
+    /// Изображение 2x2 занимает 16 байт
+    const image_size:usize=16usize;
+
+    struct Fixture{
+        directory:PathBuf,
+    }
+
+    impl Fixture{
+        fn new(name:&str)->Fixture{
+            let directory=temp_dir().join(format!("assets_{}_{}",name,std::process::id()));
+            create_dir_all(&directory).unwrap();
+            Self{directory}
+        }
+
+        fn image(&self,name:&str)->PathBuf{
+            let path=self.directory.join(format!("{}.png",name));
+            RgbaImage::new(2u32,2u32).save(&path).unwrap();
+            path
+        }
+
+        fn cache(&self,names:&[&str],settings:AssetCacheSettings)->AssetCache{
+            let mut text="images {\n".to_string();
+            for name in names{
+                text.push_str(&format!("    {} = {}\n",name,self.directory.join(format!("{}.png",name)).display()));
+            }
+            text.push_str("}\n");
+
+            let manifest=ResourceManifest::parse(&text,&ResourceResolver::new()).unwrap();
+            AssetCache::new(manifest,settings)
+        }
+    }
+
+    impl Drop for Fixture{
+        fn drop(&mut self){
+            let _=remove_dir_all(&self.directory);
+        }
+    }
+
+    fn cached(cache:&AssetCache,name:&str)->bool{
+        cache.entries.contains_key(&AssetKey::image(name))
+    }
+
+    #[test]
+    fn evicts_least_recently_used(){
+        let fixture=Fixture::new("lru");
+        for name in ["a","b","c"].iter(){
+            fixture.image(name);
+        }
+        let mut cache=fixture.cache(&["a","b","c"],AssetCacheSettings::new().memory_limit(2usize*image_size));
+
+        cache.wait(&AssetKey::image("a")).unwrap();
+        cache.wait(&AssetKey::image("b")).unwrap();
+        // Обращение к 'a' делает давно не использованным 'b'
+        assert!(cache.get(&AssetKey::image("a")).is_some());
+        cache.wait(&AssetKey::image("c")).unwrap();
+
+        assert!(cached(&cache,"a") && !cached(&cache,"b") && cached(&cache,"c"));
+        assert_eq!(cache.memory(),2usize*image_size);
+    }
+
+    #[test]
+    fn keeps_just_decoded_image(){
+        let fixture=Fixture::new("keep");
+        fixture.image("a");
+        fixture.image("b");
+        // Предел меньше одного изображения
+        let mut cache=fixture.cache(&["a","b"],AssetCacheSettings::new().memory_limit(image_size/2usize));
+
+        cache.wait(&AssetKey::image("a")).unwrap();
+        assert!(cached(&cache,"a"));
+
+        cache.wait(&AssetKey::image("b")).unwrap();
+        assert!(!cached(&cache,"a") && cached(&cache,"b"));
+        assert_eq!(cache.memory(),image_size);
+    }
+
+    #[test]
+    fn retries_after_failure(){
+        let fixture=Fixture::new("retry");
+        let mut cache=fixture.cache(&["a"],AssetCacheSettings::new());
+
+        assert!(cache.wait(&AssetKey::image("a")).is_err());
+        assert!(!cached(&cache,"a"));
+
+        let path=fixture.image("a");
+        assert!(cache.wait(&AssetKey::image("a")).is_ok());
+
+        // Готовое изображение больше не читается с диска
+        remove_file(path).unwrap();
+        assert!(cache.wait(&AssetKey::image("a")).is_ok());
+    }
+
+    #[test]
+    fn wait_fails_without_workers(){
+        let fixture=Fixture::new("workers");
+        fixture.image("a");
+        let settings=AssetCacheSettings{
+            workers:0usize,
+            ..AssetCacheSettings::new()
+        };
+        let mut cache=fixture.cache(&["a"],settings);
+
+        assert!(cache.get(&AssetKey::image("a")).is_none());
+        let error=cache.wait(&AssetKey::image("a")).unwrap_err();
+        assert!(error.to_string().contains("потоки декодирования остановлены"));
+    }
+}
@@ -0,0 +1,241 @@
+use crate::{
+    error::{GameError,GameResult},
+    objects::TextureObject,
+};
+
+use cat_engine::{
+    graphics::Graphics2D,
+    texture::Texture,
+    image::{RgbaImage,GenericImageView,imageops},
+    glium::Display,
+};
+
+/// Изображение в атласе.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct AtlasRegion{
+    /// Номер страницы (текстуры) атласа
+    pub page:usize,
+    /// Положение на странице [x,y,width,height] в пикселях
+    pub rect:[u32;4],
+    /// Текстурные координаты [x1,y1,x2,y2]
+    pub uv:[f32;4],
+}
+
+/// Текстурный атлас - небольшие изображения, собранные в общие текстуры
+/// и доступные по имени.
+pub struct Atlas{
+    /// Страницы до передачи в видеопамять
+    pages:Vec<RgbaImage>,
+    regions:Vec<(String,AtlasRegion)>,
+}
+
+impl Atlas{
+    pub const fn new()->Atlas{
+        Self{
+            pages:Vec::new(),
+            regions:Vec::new(),
+        }
+    }
+
+    pub fn region(&self,name:&str)->Option<AtlasRegion>{
+        self.regions.iter().find(|entry|entry.0==name).map(|entry|entry.1)
+    }
+
+    /// Изображение или ошибка, если его нет в атласе.
+    pub fn require(&self,name:&str)->GameResult<AtlasRegion>{
+        self.region(name).ok_or_else(||GameError::missing("atlas",name))
+    }
+
+    /// Текстурные координаты изображения.
+    pub fn uv(&self,name:&str)->Option<[f32;4]>{
+        self.region(name).map(|region|region.uv)
+    }
+
+    pub fn regions(&self)->impl Iterator<Item=(&str,AtlasRegion)>{
+        self.regions.iter().map(|entry|(entry.0.as_str(),entry.1))
+    }
+
+    /// Создание текстур страниц.
+    ///
+    /// Изображения страниц после этого не хранятся.
+    pub fn upload(&mut self,display:&Display,graphics:&mut Graphics2D)->GameResult<Vec<TextureObject>>{
+        let mut textures=Vec::with_capacity(self.pages.len());
+        for page in self.pages.drain(..){
+            let texture=Texture::from_image(&page,display).map_err(GameError::system)?;
+            textures.push(TextureObject::new(texture,graphics));
+        }
+        Ok(textures)
+    }
+}
+
+/// Сборка атласа.
+///
+/// ```text
+/// let mut builder=AtlasBuilder::new([1024u32;2]).padding(1u32);
+/// builder.add("cursor",cursor_image);
+/// builder.add_vertical_frames(&["gear","cat"],&sheet);
+/// let atlas=builder.build()?;
+/// ```
+pub struct AtlasBuilder{
+    /// Наибольший размер страницы
+    page_size:[u32;2],
+    /// Пустое место вокруг изображений, чтобы соседи не попадали
+    /// в выборку при фильтрации
+    padding:u32,
+    images:Vec<(String,RgbaImage)>,
+}
+
+impl AtlasBuilder{
+    pub fn new(page_size:[u32;2])->AtlasBuilder{
+        Self{
+            page_size,
+            padding:0u32,
+            images:Vec::new(),
+        }
+    }
+
+    pub fn padding(mut self,padding:u32)->AtlasBuilder{
+        self.padding=padding;
+        self
+    }
+
+    pub fn add<S:Into<String>>(&mut self,name:S,image:RgbaImage){
+        self.images.push((name.into(),image));
+    }
+
+    /// Добавление кадров, расположенных в картинке друг под другом,
+    /// под именами `names` сверху вниз.
+    pub fn add_vertical_frames(&mut self,names:&[&str],sheet:&RgbaImage){
+        let (width,height)=sheet.dimensions();
+        let frame_height=height/names.len().max(1usize) as u32;
+
+        for (c,name) in names.iter().enumerate(){
+            let frame=imageops::crop_imm(sheet,0u32,frame_height*c as u32,width,frame_height).to_image();
+            self.add(*name,frame);
+        }
+    }
+
+    pub fn build(self)->GameResult<Atlas>{
+        let sizes:Vec<[u32;2]>=self.images.iter().map(|(_,image)|{
+            let (width,height)=image.dimensions();
+            [width,height]
+        }).collect();
+
+        let places=match pack(&sizes,self.page_size,self.padding){
+            Some(places)=>places,
+            None=>{
+                let name=self.images.iter().zip(sizes.iter())
+                        .find(|(_,size)|!fits(**size,self.page_size,self.padding))
+                        .map_or("",|((name,_),_)|name.as_str());
+                return Err(GameError::failed(name,"изображение больше страницы атласа"))
+            }
+        };
+
+        // Размер страницы по занятой области
+        let page_count=places.iter().map(|place|place.0+1usize).max().unwrap_or(0usize);
+        let mut page_sizes=vec![[0u32;2];page_count];
+        for ((page,[x,y]),[width,height]) in places.iter().zip(sizes.iter()){
+            let page_size=&mut page_sizes[*page];
+            page_size[0]=page_size[0].max(x+width+self.padding);
+            page_size[1]=page_size[1].max(y+height+self.padding);
+        }
+
+        let mut pages:Vec<RgbaImage>=page_sizes.iter().map(|&[width,height]|RgbaImage::new(width,height)).collect();
+        let mut regions=Vec::with_capacity(self.images.len());
+
+        for ((name,image),&(page,[x,y])) in self.images.iter().zip(places.iter()){
+            imageops::replace(&mut pages[page],image,x,y);
+
+            let (width,height)=image.dimensions();
+            let [page_width,page_height]=page_sizes[page];
+            regions.push((name.clone(),AtlasRegion{
+                page,
+                rect:[x,y,width,height],
+                uv:[
+                    x as f32/page_width as f32,
+                    y as f32/page_height as f32,
+                    (x+width) as f32/page_width as f32,
+                    (y+height) as f32/page_height as f32,
+                ],
+            }));
+        }
+
+        Ok(Atlas{
+            pages,
+            regions,
+        })
+    }
+}
+
+fn fits([width,height]:[u32;2],[page_width,page_height]:[u32;2],padding:u32)->bool{
+    width+2u32*padding<=page_width && height+2u32*padding<=page_height
+}
+
+/// Размещение прямоугольников `sizes` на страницах полками:
+/// по убыванию высоты слева направо, затем следующая полка ниже.
+///
+/// Возвращает страницу и левый верхний угол для каждого размера
+/// в исходном порядке или `None`, если что-то не помещается на страницу.
+pub fn pack(sizes:&[[u32;2]],page_size:[u32;2],padding:u32)->Option<Vec<(usize,[u32;2])>>{
+    if sizes.iter().any(|&size|!fits(size,page_size,padding)){
+        return None
+    }
+
+    let mut order:Vec<usize>=(0..sizes.len()).collect();
+    order.sort_by(|&a,&b|sizes[b][1].cmp(&sizes[a][1]));
+
+    let mut places=vec![(0usize,[0u32;2]);sizes.len()];
+
+    let mut page=0usize;
+    // Левый верхний угол свободного места на полке и высота полки
+    let mut x=padding;
+    let mut y=padding;
+    let mut shelf_height=0u32;
+
+    for index in order{
+        let [width,height]=sizes[index];
+
+        // Новая полка
+        if x+width+padding>page_size[0]{
+            x=padding;
+            y+=shelf_height+padding;
+            shelf_height=0u32;
+        }
+
+        // Новая страница
+        if y+height+padding>page_size[1]{
+            page+=1;
+            x=padding;
+            y=padding;
+            shelf_height=0u32;
+        }
+
+        places[index]=(page,[x,y]);
+        x+=width+padding;
+        shelf_height=shelf_height.max(height);
+    }
+
+    Some(places)
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn packs_shelves_and_pages(){
+        let sizes=[[30u32,10u32],[40u32,20u32],[50u32,20u32],[100u32,60u32]];
+        let places=pack(&sizes,[100u32,100u32],0u32).unwrap();
+
+        // Самое высокое - первым, затем полка из двух по 20
+        assert_eq!(places[3],(0usize,[0u32,0u32]));
+        assert_eq!(places[1],(0usize,[0u32,60u32]));
+        assert_eq!(places[2],(0usize,[40u32,60u32]));
+        assert_eq!(places[0],(0usize,[0u32,80u32]));
+
+        let places=pack(&[[60u32,60u32],[60u32,60u32]],[100u32,100u32],1u32).unwrap();
+        assert_eq!(places,vec![(0usize,[1u32,1u32]),(1usize,[1u32,1u32])]);
+
+        assert!(pack(&[[100u32,100u32]],[100u32,100u32],1u32).is_none());
+    }
+}
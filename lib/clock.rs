@@ -0,0 +1,104 @@
+use std::time::{Duration,Instant};
+
+/// Наибольший шаг времени по умолчанию в секундах.
+///
+/// После долгой остановки (перетаскивание окна, загрузка)
+/// анимации продолжаются, а не перескакивают в конец.
+const default_max_step:f32=0.1f32;
+
+/// Часы игры - время между обновлениями.
+///
+/// Анимации задаются в секундах и получают прошедшее время,
+/// поэтому их скорость не зависит от частоты обновлений
+/// и пропущенных кадров.
+pub struct Clock{
+    last:Option<Instant>,
+    /// Время с предыдущего обновления в секундах
+    elapsed:f32,
+    /// Время с первого обновления в секундах
+    total:f64,
+    max_step:f32,
+}
+
+impl Clock{
+    pub const fn new()->Clock{
+        Self{
+            last:None,
+            elapsed:0f32,
+            total:0f64,
+            max_step:default_max_step,
+        }
+    }
+
+    /// Наибольший шаг времени в секундах.
+    pub fn max_step(mut self,max_step:f32)->Clock{
+        self.max_step=max_step;
+        self
+    }
+
+    /// Отметка нового обновления.
+    /// Возвращает время с предыдущего обновления в секундах.
+    pub fn tick(&mut self)->f32{
+        let now=Instant::now();
+
+        let elapsed=match self.last{
+            Some(last)=>now.duration_since(last),
+            None=>Duration::from_secs(0),
+        };
+        self.last=Some(now);
+
+        self.advance(elapsed.as_secs_f32())
+    }
+
+    /// Перевод часов на данное время в секундах.
+    pub fn advance(&mut self,elapsed:f32)->f32{
+        self.elapsed=elapsed.max(0f32).min(self.max_step);
+        self.total+=self.elapsed as f64;
+        self.elapsed
+    }
+
+    /// Время с предыдущего обновления в секундах.
+    pub fn elapsed(&self)->f32{
+        self.elapsed
+    }
+
+    /// Время с первого обновления в секундах.
+    pub fn total(&self)->f64{
+        self.total
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn advance_accumulates_and_clamps(){
+        let mut clock=Clock::new();
+        assert_eq!(clock.advance(0.02f32),0.02f32);
+        assert_eq!(clock.advance(0.03f32),0.03f32);
+        assert_eq!(clock.elapsed(),0.03f32);
+        assert!((clock.total()-0.05f64).abs()<1e-6f64);
+
+        // Долгая остановка и отрицательный шаг
+        assert_eq!(clock.advance(5f32),default_max_step);
+        assert_eq!(clock.advance(-1f32),0f32);
+        assert!((clock.total()-0.15f64).abs()<1e-6f64);
+
+        let mut clock=Clock::new().max_step(1f32);
+        assert_eq!(clock.advance(0.5f32),0.5f32);
+        assert_eq!(clock.advance(3f32),1f32);
+    }
+
+    #[test]
+    fn tick_after_stall(){
+        let mut clock=Clock::new();
+        // Первое обновление - без прошедшего времени
+        assert_eq!(clock.tick(),0f32);
+
+        // Окно перетаскивали несколько секунд
+        clock.last=Some(Instant::now()-Duration::from_secs(5));
+        assert_eq!(clock.tick(),default_max_step);
+        assert!(clock.tick()<=default_max_step);
+    }
+}
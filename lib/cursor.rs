@@ -0,0 +1,174 @@
+use crate::{
+    colours::White,
+    atlas::Atlas,
+    objects::{TextureObject,TexturedObject},
+};
+
+use cat_engine::{
+    mouse_cursor,
+    Window,
+    graphics::{Graphics,Graphics2D},
+    texture::ImageObject,
+    glium::Surface,
+};
+
+/// Состояние курсора.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum CursorState{
+    Default=0,
+    /// Над кнопкой
+    Hover=1,
+    /// Над полем ввода
+    TextInput=2,
+    /// Идёт загрузка
+    Busy=3,
+}
+
+impl CursorState{
+    pub const all:[CursorState;4]=[
+        CursorState::Default,
+        CursorState::Hover,
+        CursorState::TextInput,
+        CursorState::Busy,
+    ];
+
+    /// Имя изображения состояния в манифесте и атласе.
+    pub fn image_name(self)->&'static str{
+        match self{
+            CursorState::Default=>"cursor",
+            CursorState::Hover=>"cursor_hover",
+            CursorState::TextInput=>"cursor_text",
+            CursorState::Busy=>"cursor_busy",
+        }
+    }
+}
+
+/// Настройки курсора.
+pub struct CursorSettings{
+    /// Размер изображений курсора в пикселях окна
+    size:f32,
+    /// Горячая точка каждого состояния в пикселях изображения
+    /// (`None` - центр изображения)
+    hotspots:[Option<[f32;2]>;4],
+}
+
+impl CursorSettings{
+    pub const fn new(size:f32)->CursorSettings{
+        Self{
+            size,
+            hotspots:[None;4],
+        }
+    }
+
+    /// Горячая точка изображения состояния `state` в пикселях изображения.
+    pub fn hotspot(mut self,state:CursorState,hotspot:[f32;2])->CursorSettings{
+        self.hotspots[state as usize]=Some(hotspot);
+        self
+    }
+}
+
+struct CursorImage{
+    object:TexturedObject,
+    uv:[f32;4],
+    /// Горячая точка в долях размера изображения
+    hotspot:[f32;2],
+}
+
+/// Курсор мыши из изображений атласа.
+///
+/// Для состояний без своего изображения используется обычный курсор.
+/// Рисуется поверх всех сцен (`draw` после отрисовки сцен).
+/// Если нет даже обычного изображения, остаётся системный курсор.
+pub struct Cursor{
+    images:Vec<Option<CursorImage>>,
+    size:f32,
+    state:CursorState,
+    /// Системный курсор вместо изображений
+    system:bool,
+}
+
+impl Cursor{
+    /// Создание изображений состояний из атласа
+    /// (`textures` - текстуры страниц атласа).
+    pub fn new(settings:CursorSettings,atlas:&Atlas,textures:&[TextureObject],graphics:&mut Graphics2D)->Cursor{
+        let size=settings.size;
+
+        let images=CursorState::all.iter().map(|&state|{
+            let region=atlas.region(state.image_name())?;
+            let texture=textures.get(region.page)?;
+
+            let image=ImageObject::raw_uv([0f32,0f32,size,size],region.uv,White);
+            let object=TexturedObject::new(&image,texture,graphics)?;
+
+            let [_,_,width,height]=region.rect;
+            let hotspot=match settings.hotspots[state as usize]{
+                Some([x,y])=>[x/width.max(1u32) as f32,y/height.max(1u32) as f32],
+                None=>[0.5f32;2],
+            };
+
+            Some(CursorImage{
+                object,
+                uv:region.uv,
+                hotspot,
+            })
+        }).collect::<Vec<_>>();
+
+        Self{
+            system:images[CursorState::Default as usize].is_none(),
+            images,
+            size,
+            state:CursorState::Default,
+        }
+    }
+
+    pub fn state(&self)->CursorState{
+        self.state
+    }
+
+    pub fn set_state(&mut self,state:CursorState){
+        self.state=state;
+    }
+
+    /// Используется ли системный курсор.
+    pub fn system(&self)->bool{
+        self.system
+    }
+
+    /// Выбор системного курсора или изображений.
+    ///
+    /// Без обычного изображения системный курсор не выключается.
+    pub fn set_system(&mut self,system:bool,window:&Window){
+        self.system=system || self.images[CursorState::Default as usize].is_none();
+        window.display().gl_window().window().set_cursor_visible(self.system);
+    }
+
+    /// Изменение размера изображений (в пикселях окна).
+    pub fn resize(&mut self,size:f32,graphics:&mut Graphics2D){
+        self.size=size;
+        for image in self.images.iter().flatten(){
+            let image_base=ImageObject::raw_uv([0f32,0f32,size,size],image.uv,White);
+            image.object.rewrite_vertices(&image_base,graphics);
+        }
+    }
+
+    fn image(&self)->Option<&CursorImage>{
+        self.images[self.state as usize].as_ref()
+            .or_else(||self.images[CursorState::Default as usize].as_ref())
+    }
+
+    /// Отрисовка горячей точкой в положении курсора мыши.
+    pub fn draw<S:Surface>(&self,graphics:&mut Graphics<S>){
+        if self.system{
+            return
+        }
+
+        if let Some(image)=self.image(){
+            let [x,y]=unsafe{mouse_cursor.position()};
+            let shift=[
+                x-image.hotspot[0]*self.size,
+                y-image.hotspot[1]*self.size,
+            ];
+            image.object.draw_shift(shift,graphics);
+        }
+    }
+}
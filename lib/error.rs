@@ -0,0 +1,88 @@
+use std::{
+    fmt,
+    path::{Path,PathBuf},
+};
+
+pub type GameResult<T>=Result<T,GameError>;
+
+/// Ошибка игры.
+#[derive(Clone,Debug,PartialEq)]
+pub enum GameError{
+    /// Загрузка отменена
+    Cancelled,
+    /// Ресурс не объявлен в манифесте
+    MissingResource{
+        /// Раздел манифеста
+        section:&'static str,
+        name:String,
+    },
+    /// Не удалось прочитать или разобрать файл
+    File{
+        path:PathBuf,
+        reason:String,
+    },
+    /// Сбой при загрузке ресурса `item` (например, паника в потоке загрузки)
+    Failed{
+        item:String,
+        reason:String,
+    },
+    /// Ошибка окна, графики или звука
+    System(String),
+}
+
+impl GameError{
+    pub fn file<P:Into<PathBuf>,R:ToString>(path:P,reason:R)->GameError{
+        GameError::File{
+            path:path.into(),
+            reason:reason.to_string(),
+        }
+    }
+
+    pub fn missing<N:ToString>(section:&'static str,name:N)->GameError{
+        GameError::MissingResource{
+            section,
+            name:name.to_string(),
+        }
+    }
+
+    pub fn failed<I:ToString,R:ToString>(item:I,reason:R)->GameError{
+        GameError::Failed{
+            item:item.to_string(),
+            reason:reason.to_string(),
+        }
+    }
+
+    pub fn system<R:ToString>(reason:R)->GameError{
+        GameError::System(reason.to_string())
+    }
+
+    /// Файл, который не удалось загрузить.
+    pub fn path(&self)->Option<&Path>{
+        match self{
+            GameError::File{path,..}=>Some(path),
+            _=>None,
+        }
+    }
+
+    /// Причина ошибки без пути к файлу.
+    pub fn reason(&self)->String{
+        match self{
+            GameError::Cancelled=>"загрузка отменена".to_string(),
+            GameError::MissingResource{section,name}=>format!("'{}' нет в разделе '{}' манифеста",name,section),
+            GameError::File{reason,..}=>reason.clone(),
+            GameError::Failed{item,reason}=>format!("{}: {}",item,reason),
+            GameError::System(reason)=>reason.clone(),
+        }
+    }
+}
+
+impl fmt::Display for GameError{
+    fn fmt(&self,f:&mut fmt::Formatter)->fmt::Result{
+        match self.path(){
+            Some(path)=>write!(f,"{}: {}",path.display(),self.reason()),
+            None=>write!(f,"{}",self.reason()),
+        }
+    }
+}
+
+impl std::error::Error for GameError{}
@@ -0,0 +1,96 @@
+use cat_engine::{
+    graphics::Graphics2D,
+    glium::Display,
+};
+
+use std::{
+    collections::BTreeSet,
+    fs::read_to_string,
+    io::Error,
+    path::Path,
+    fmt::{self,Formatter,Write},
+};
+
+/// Набор символов для кэширования шрифтов.
+///
+/// Собирается из текстов, которые действительно выводятся на экран,
+/// чтобы не держать в кэше лишние символы и не терять нужные.
+pub struct CharacterSet{
+    characters:BTreeSet<char>,
+}
+
+impl CharacterSet{
+    pub fn new()->CharacterSet{
+        Self{
+            characters:BTreeSet::new(),
+        }
+    }
+
+    /// Добавление всех символов строки (кроме управляющих).
+    pub fn insert_str(&mut self,text:&str){
+        for character in text.chars(){
+            if !character.is_control(){
+                self.characters.insert(character);
+            }
+        }
+    }
+
+    /// Добавление всех символов текстового файла.
+    pub fn insert_file<P:AsRef<Path>>(&mut self,path:P)->Result<(),Error>{
+        let text=read_to_string(path)?;
+        self.insert_str(&text);
+        Ok(())
+    }
+
+    pub fn contains(&self,character:char)->bool{
+        self.characters.contains(&character)
+    }
+
+    pub fn len(&self)->usize{
+        self.characters.len()
+    }
+
+    pub fn is_empty(&self)->bool{
+        self.characters.is_empty()
+    }
+
+    pub fn chars<'a>(&'a self)->impl Iterator<Item=char>+'a{
+        self.characters.iter().cloned()
+    }
+}
+
+/// Все символы одной строкой.
+impl fmt::Display for CharacterSet{
+    fn fmt(&self,f:&mut Formatter)->fmt::Result{
+        for character in self.chars(){
+            f.write_char(character)?
+        }
+        Ok(())
+    }
+}
+
+/// Кэширование символа, если его ещё нет в кэше шрифта.
+///
+/// Используется для символов, введённых во время игры.
+pub fn cache_character(character:char,font:usize,display:&Display,graphics:&mut Graphics2D){
+    let font=graphics.get_font_mut(font);
+    if !font.contains_char(character){
+        font.insert_char(character,display);
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn collects_sorted_characters(){
+        let mut characters=CharacterSet::new();
+        assert!(characters.is_empty());
+
+        characters.insert_str("баа\n\tab");
+        assert_eq!(characters.len(),4);
+        assert!(!characters.contains('\n'));
+        assert_eq!(characters.to_string(),"abаб");
+    }
+}
@@ -0,0 +1,266 @@
+use crate::{
+    assets::load_image,
+    error::{GameError,GameResult},
+    log,
+};
+
+use cat_engine::image::RgbaImage;
+
+use std::{
+    fs::{read,read_dir,create_dir_all,remove_file,rename,write},
+    path::{Path,PathBuf},
+};
+
+/// Метка файла кэша
+const magic:&'static [u8;4]=b"VNSI";
+/// Метка, ширина и высота
+const header_size:usize=12usize;
+
+/// Хэш FNV-1a (не зависит от версии компилятора, в отличие от `DefaultHasher`).
+pub fn fnv1a(bytes:&[u8])->u64{
+    let mut hash=0xcbf29ce484222325u64;
+    for &byte in bytes{
+        hash^=byte as u64;
+        hash=hash.wrapping_mul(0x100000001b3u64);
+    }
+    hash
+}
+
+/// Дисковый кэш изображений с изменённым размером.
+///
+/// Файл кэша называется `[хэш пути]_[хэш содержимого]_[ширина]x[высота]`
+/// и хранит несжатые пиксели RGBA, поэтому читается без декодирования.
+/// При изменении исходного файла или размера создаётся новый файл,
+/// а старые файлы того же изображения удаляются.
+#[derive(Clone)]
+pub struct ScaledImageCache{
+    directory:PathBuf,
+}
+
+impl ScaledImageCache{
+    pub fn new<P:Into<PathBuf>>(directory:P)->ScaledImageCache{
+        Self{
+            directory:directory.into(),
+        }
+    }
+
+    /// Изображение `path` размера `size` из кэша
+    /// или загруженное с изменением размера и сохранённое в кэш.
+    pub fn load<P:AsRef<Path>>(&self,path:P,size:[u32;2])->GameResult<RgbaImage>{
+        let path=path.as_ref();
+        let source=read(path).map_err(|e|GameError::file(path,e))?;
+
+        let prefix=format!("{:016x}_",fnv1a(path.to_string_lossy().as_bytes()));
+        let name=format!("{}{:016x}_{}x{}",prefix,fnv1a(&source),size[0],size[1]);
+        let cached=self.directory.join(&name);
+
+        if let Some(image)=read_cached(&cached,size){
+            return Ok(image)
+        }
+
+        let image=load_image(path,Some(size))?;
+
+        // Сбой записи не мешает игре - изображение просто не кэшируется
+        if let Err(e)=self.store(&prefix,&name,&image,size){
+            log::warning(format!("кэш изображений: {} не сохранён: {}",cached.display(),e));
+        }
+        else{
+            log::debug(format!("кэш изображений: {} сохранён",cached.display()));
+        }
+
+        Ok(image)
+    }
+
+    fn store(&self,prefix:&str,name:&str,image:&RgbaImage,[width,height]:[u32;2])->std::io::Result<()>{
+        create_dir_all(&self.directory)?;
+
+        // Старые версии этого изображения
+        for entry in read_dir(&self.directory)?.flatten(){
+            let file_name=entry.file_name();
+            let file_name=file_name.to_string_lossy();
+            // Временные файлы могут ещё записываться другим потоком
+            if file_name.starts_with(prefix) && file_name!=name && !file_name.ends_with(".tmp"){
+                let _=remove_file(entry.path());
+            }
+        }
+
+        let mut bytes=Vec::with_capacity(header_size+image.as_raw().len());
+        bytes.extend_from_slice(magic);
+        bytes.extend_from_slice(&width.to_le_bytes());
+        bytes.extend_from_slice(&height.to_le_bytes());
+        bytes.extend_from_slice(image.as_raw());
+
+        // Запись через временный файл, чтобы не оставить обрезанный кэш
+        let temporary=self.directory.join(format!("{}.tmp",name));
+        write(&temporary,&bytes)?;
+        rename(&temporary,self.directory.join(name))
+    }
+}
+
+fn read_cached(path:&Path,[width,height]:[u32;2])->Option<RgbaImage>{
+    let mut bytes=read(path).ok()?;
+
+    if bytes.len()<header_size || &bytes[0..4]!=magic{
+        return None
+    }
+
+    let mut number=[0u8;4];
+    number.copy_from_slice(&bytes[4..8]);
+    let cached_width=u32::from_le_bytes(number);
+    number.copy_from_slice(&bytes[8..12]);
+    let cached_height=u32::from_le_bytes(number);
+
+    if [cached_width,cached_height]!=[width,height]{
+        return None
+    }
+
+    let pixels=bytes.split_off(header_size);
+    RgbaImage::from_raw(width,height,pixels)
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    use std::{
+        env::temp_dir,
+        fs::remove_dir_all,
+    };
+
+    struct Fixture{
+        directory:PathBuf,
+        source:PathBuf,
+        cache:ScaledImageCache,
+    }
+
+    impl Fixture{
+        fn new(name:&str)->Fixture{
+            let directory=temp_dir().join(format!("image_cache_{}_{}",name,std::process::id()));
+            create_dir_all(&directory).unwrap();
+            let source=directory.join("source.png");
+            RgbaImage::new(2u32,2u32).save(&source).unwrap();
+            let cache=ScaledImageCache::new(directory.join("cache"));
+            Self{directory,source,cache}
+        }
+
+        /// Отсортированные имена файлов кэша.
+        fn files(&self)->Vec<String>{
+            let mut files:Vec<String>=read_dir(&self.cache.directory).unwrap()
+                .flatten()
+                .map(|entry|entry.file_name().to_string_lossy().into_owned())
+                .collect();
+            files.sort();
+            files
+        }
+
+        fn prefix(&self)->String{
+            format!("{:016x}_",fnv1a(self.source.to_string_lossy().as_bytes()))
+        }
+    }
+
+    impl Drop for Fixture{
+        fn drop(&mut self){
+            let _=remove_dir_all(&self.directory);
+        }
+    }
+
+    /// Файл кэша с заданными пикселями.
+    fn cache_file(width:u32,height:u32,pixel:u8)->Vec<u8>{
+        let mut bytes=magic.to_vec();
+        bytes.extend_from_slice(&width.to_le_bytes());
+        bytes.extend_from_slice(&height.to_le_bytes());
+        bytes.extend(std::iter::repeat(pixel).take((width*height*4) as usize));
+        bytes
+    }
+
+    #[test]
+    fn reads_stored_image(){
+        let fixture=Fixture::new("hit");
+        fixture.cache.load(&fixture.source,[4u32,4u32]).unwrap();
+
+        let files=fixture.files();
+        assert_eq!(files.len(),1usize);
+        assert!(files[0].starts_with(&fixture.prefix()));
+        assert!(files[0].ends_with("_4x4"));
+
+        // Изображение читается из кэша, а не из исходного файла
+        write(fixture.cache.directory.join(&files[0]),cache_file(4u32,4u32,7u8)).unwrap();
+        let image=fixture.cache.load(&fixture.source,[4u32,4u32]).unwrap();
+        assert!(image.as_raw().iter().all(|&byte|byte==7u8));
+        assert_eq!(fixture.files(),files);
+    }
+
+    #[test]
+    fn changed_source_misses_and_removes_old_entry(){
+        let fixture=Fixture::new("source");
+        fixture.cache.load(&fixture.source,[4u32,4u32]).unwrap();
+        let old=fixture.files();
+
+        RgbaImage::new(3u32,3u32).save(&fixture.source).unwrap();
+        fixture.cache.load(&fixture.source,[4u32,4u32]).unwrap();
+        let new=fixture.files();
+
+        assert_eq!(new.len(),1usize);
+        assert_ne!(new,old);
+        assert!(new[0].starts_with(&fixture.prefix()));
+    }
+
+    #[test]
+    fn changed_size_misses_and_removes_old_entry(){
+        let fixture=Fixture::new("size");
+        fixture.cache.load(&fixture.source,[4u32,4u32]).unwrap();
+        let image=fixture.cache.load(&fixture.source,[8u32,6u32]).unwrap();
+
+        assert_eq!(image.as_raw().len(),8usize*6usize*4usize);
+        let files=fixture.files();
+        assert_eq!(files.len(),1usize);
+        assert!(files[0].ends_with("_8x6"));
+    }
+
+    #[test]
+    fn keeps_temporary_files(){
+        let fixture=Fixture::new("temporary");
+        create_dir_all(&fixture.cache.directory).unwrap();
+        let temporary=format!("{}0000000000000000_4x4.tmp",fixture.prefix());
+        write(fixture.cache.directory.join(&temporary),b"").unwrap();
+
+        fixture.cache.load(&fixture.source,[4u32,4u32]).unwrap();
+
+        assert!(fixture.files().contains(&temporary));
+    }
+
+    #[test]
+    fn rejects_broken_files(){
+        let fixture=Fixture::new("broken");
+        create_dir_all(&fixture.cache.directory).unwrap();
+        let path=fixture.cache.directory.join("entry");
+
+        write(&path,cache_file(2u32,2u32,1u8)).unwrap();
+        assert!(read_cached(&path,[2u32,2u32]).is_some());
+        // Другой размер
+        assert!(read_cached(&path,[2u32,3u32]).is_none());
+
+        // Обрезанный файл
+        let mut bytes=cache_file(2u32,2u32,1u8);
+        bytes.pop();
+        write(&path,&bytes).unwrap();
+        assert!(read_cached(&path,[2u32,2u32]).is_none());
+
+        // Обрезанный заголовок
+        write(&path,&magic[..]).unwrap();
+        assert!(read_cached(&path,[2u32,2u32]).is_none());
+
+        // Чужой файл того же размера
+        let mut bytes=cache_file(2u32,2u32,1u8);
+        bytes[0..4].copy_from_slice(b"PNG\0");
+        write(&path,&bytes).unwrap();
+        assert!(read_cached(&path,[2u32,2u32]).is_none());
+    }
+
+    #[test]
+    fn fnv1a_matches_reference(){
+        assert_eq!(fnv1a(b""),0xcbf29ce484222325u64);
+        assert_eq!(fnv1a(b"a"),0xaf63dc4c8601ec8cu64);
+        assert_eq!(fnv1a(b"foobar"),0x85944171f73967e8u64);
+    }
+}
@@ -4,7 +4,51 @@ pub mod user_interface;
 
 pub mod colours;
 
-pub mod loading_flag;
+pub mod error;
+
+pub mod log;
+
+pub mod loader;
+
+pub mod manifest;
+
+pub mod page_table;
+
+pub mod assets;
+
+pub mod image_cache;
+
+pub mod atlas;
+
+pub mod cursor;
+
+pub mod screenshot;
+
+pub mod resources;
+
+pub mod localization;
+
+pub mod glyphs;
+
+pub mod player_name;
+
+pub mod scene;
+
+pub mod objects;
+
+pub mod transition;
+
+pub mod clock;
+
+pub mod animation;
+
+pub mod particles;
+
+pub mod parallax;
+
+pub mod viewport;
+
+pub mod theme;
 
 // Выравнивание
 #[derive(Clone)]
@@ -0,0 +1,291 @@
+use crate::{
+    error::GameError,
+    log,
+};
+
+use std::{
+    sync::{
+        Arc,
+        Mutex,
+        atomic::{AtomicBool,AtomicUsize,Ordering},
+    },
+    thread::{spawn,JoinHandle},
+};
+
+/// Состояние загрузки, общее для потоков.
+struct Shared{
+    cancelled:AtomicBool,
+    done:AtomicUsize,
+    total:AtomicUsize,
+    /// Имя загружаемого ресурса
+    item:Mutex<String>,
+}
+
+/// Ход загрузки.
+#[derive(Clone,Debug,PartialEq)]
+pub struct Progress{
+    pub done:usize,
+    pub total:usize,
+    /// Имя загружаемого ресурса
+    pub item:String,
+}
+
+impl Progress{
+    /// Доля загруженного от 0 до 1.
+    pub fn fraction(&self)->f32{
+        if self.total==0{
+            0f32
+        }
+        else{
+            (self.done as f32/self.total as f32).min(1f32)
+        }
+    }
+}
+
+/// Отчёт о ходе загрузки из потока загрузки.
+pub struct LoadingProgress{
+    shared:Arc<Shared>,
+}
+
+impl LoadingProgress{
+    /// Общее количество загружаемых ресурсов.
+    pub fn set_total(&self,total:usize){
+        self.shared.total.store(total,Ordering::Release);
+    }
+
+    /// Начало загрузки ресурса `item`.
+    ///
+    /// Возвращает `GameError::Cancelled`, если загрузка отменена,
+    /// поэтому вызывается через `?` перед каждым ресурсом.
+    pub fn begin(&self,item:&str)->Result<(),GameError>{
+        if self.is_cancelled(){
+            return Err(GameError::Cancelled)
+        }
+
+        log::debug(format!("загрузка '{}'",item));
+        match self.shared.item.lock(){
+            Ok(mut current)=>*current=item.to_string(),
+            Err(poisoned)=>*poisoned.into_inner()=item.to_string(),
+        }
+        Ok(())
+    }
+
+    /// Ресурс загружен.
+    pub fn finish_item(&self){
+        self.shared.done.fetch_add(1,Ordering::AcqRel);
+    }
+
+    pub fn is_cancelled(&self)->bool{
+        self.shared.cancelled.load(Ordering::Acquire)
+    }
+}
+
+/// Загрузка в отдельном потоке с отчётом о ходе и отменой.
+///
+/// Паника в потоке загрузки превращается в `GameError::Failed`
+/// для ресурса, который загружался в этот момент.
+///
+/// При удалении загрузка отменяется без ожидания потока.
+pub struct Loader<T:Send+'static>{
+    shared:Arc<Shared>,
+    thread:Option<JoinHandle<Result<T,GameError>>>,
+}
+
+impl<T:Send+'static> Loader<T>{
+    pub fn spawn<F>(load:F)->Loader<T>
+        where F:FnOnce(&LoadingProgress)->Result<T,GameError>+Send+'static
+    {
+        let shared=Arc::new(Shared{
+            cancelled:AtomicBool::new(false),
+            done:AtomicUsize::new(0usize),
+            total:AtomicUsize::new(0usize),
+            item:Mutex::new(String::new()),
+        });
+
+        let progress=LoadingProgress{
+            shared:shared.clone(),
+        };
+
+        let thread=spawn(move||load(&progress));
+
+        Self{
+            shared,
+            thread:Some(thread),
+        }
+    }
+
+    pub fn progress(&self)->Progress{
+        Progress{
+            done:self.shared.done.load(Ordering::Acquire),
+            total:self.shared.total.load(Ordering::Acquire),
+            item:self.item(),
+        }
+    }
+
+    fn item(&self)->String{
+        match self.shared.item.lock(){
+            Ok(item)=>item.clone(),
+            // Поток упал, удерживая имя
+            Err(poisoned)=>poisoned.into_inner().clone(),
+        }
+    }
+
+    /// Поток загрузки завершён (результат можно забрать).
+    pub fn is_finished(&self)->bool{
+        match &self.thread{
+            Some(thread)=>thread.is_finished(),
+            None=>true,
+        }
+    }
+
+    /// Результат загрузки, если поток завершён.
+    ///
+    /// Возвращается один раз.
+    pub fn take(&mut self)->Option<Result<T,GameError>>{
+        if !self.is_finished(){
+            return None
+        }
+
+        let thread=self.thread.take()?;
+        Some(self.join(thread))
+    }
+
+    /// Отмена загрузки без ожидания завершения потока.
+    ///
+    /// Поток завершается сам при следующей проверке отмены,
+    /// а его результат можно забрать через `take`.
+    pub fn cancel(&self){
+        self.shared.cancelled.store(true,Ordering::Release);
+    }
+
+    /// Отмена загрузки с ожиданием завершения потока.
+    ///
+    /// Блокирует вызывающий поток, поэтому не используется во время кадра.
+    pub fn cancel_and_wait(&mut self)->Option<Result<T,GameError>>{
+        self.cancel();
+        let thread=self.thread.take()?;
+        Some(self.join(thread))
+    }
+
+    fn join(&self,thread:JoinHandle<Result<T,GameError>>)->Result<T,GameError>{
+        match thread.join(){
+            Ok(result)=>result,
+            Err(panic)=>{
+                let reason=if let Some(message)=panic.downcast_ref::<&str>(){
+                    message.to_string()
+                }
+                else if let Some(message)=panic.downcast_ref::<String>(){
+                    message.clone()
+                }
+                else{
+                    "неизвестная ошибка".to_string()
+                };
+
+                Err(GameError::failed(self.item(),reason))
+            }
+        }
+    }
+}
+
+impl<T:Send+'static> Drop for Loader<T>{
+    fn drop(&mut self){
+        // Поток не ожидается - его результат отбрасывается
+        self.cancel();
+        self.thread=None;
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    use std::time::{Duration,Instant};
+
+    use std::sync::mpsc::channel;
+
+    /// Ожидание результата без отмены.
+    fn wait<T:Send+'static>(loader:&mut Loader<T>)->Result<T,GameError>{
+        loop{
+            if let Some(result)=loader.take(){
+                return result
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn drop_does_not_wait(){
+        let loader=Loader::spawn(|progress|{
+            // Долгое декодирование без проверки отмены
+            std::thread::sleep(Duration::from_millis(500));
+            progress.begin("после отмены")?;
+            Ok(1u32)
+        });
+
+        let start=Instant::now();
+        drop(loader);
+        assert!(start.elapsed()<Duration::from_millis(250));
+    }
+
+    #[test]
+    fn reports_progress(){
+        let (step_sender,step)=channel::<()>();
+        let (reached,reached_receiver)=channel::<()>();
+
+        let mut loader=Loader::spawn(move|progress|{
+            progress.set_total(2usize);
+            for item in ["first","second"].iter(){
+                progress.begin(item)?;
+                reached.send(()).unwrap();
+                step.recv().unwrap();
+                progress.finish_item();
+            }
+            Ok(2u32)
+        });
+
+        reached_receiver.recv().unwrap();
+        assert_eq!(loader.progress(),Progress{done:0usize,total:2usize,item:"first".to_string()});
+
+        step_sender.send(()).unwrap();
+        reached_receiver.recv().unwrap();
+        let progress=loader.progress();
+        assert_eq!((progress.done,progress.item.as_str()),(1usize,"second"));
+        assert_eq!(progress.fraction(),0.5f32);
+
+        step_sender.send(()).unwrap();
+        assert_eq!(wait(&mut loader).unwrap(),2u32);
+        assert_eq!(loader.progress().fraction(),1f32);
+        assert!(loader.take().is_none());
+    }
+
+    #[test]
+    fn cancellation_stops_at_next_item(){
+        let (step_sender,step)=channel::<()>();
+
+        let mut loader=Loader::spawn(move|progress|{
+            progress.begin("first")?;
+            step.recv().unwrap();
+            progress.finish_item();
+            progress.begin("second")?;
+            Ok(())
+        });
+
+        loader.cancel();
+        step_sender.send(()).unwrap();
+        match loader.cancel_and_wait(){
+            Some(Err(GameError::Cancelled))=>{}
+            _=>panic!("загрузка не отменена"),
+        }
+    }
+
+    #[test]
+    fn panic_becomes_error(){
+        let mut loader=Loader::<()>::spawn(|progress|{
+            progress.begin("broken.png")?;
+            panic!("повреждённый файл")
+        });
+
+        let message=wait(&mut loader).unwrap_err().to_string();
+        assert!(message.contains("broken.png") && message.contains("повреждённый файл"),"{}",message);
+    }
+}
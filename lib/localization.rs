@@ -0,0 +1,279 @@
+use crate::{
+    error::{GameError,GameResult},
+    log,
+    manifest::ManifestEntry,
+    resources::ResourceResolver,
+};
+
+use std::{
+    fs::read_to_string,
+    io::{Error,ErrorKind},
+    path::{Path,PathBuf},
+};
+
+/// Таблица строк одного языка.
+///
+/// Формат файла:
+/// ```text
+/// [ключ] = [строка]
+/// ```
+/// Строки, начинающиеся с `//`, пропускаются.
+pub struct StringTable{
+    /// Отсортированные по ключу пары
+    entries:Vec<(String,String)>,
+}
+
+impl StringTable{
+    pub const fn new()->StringTable{
+        Self{
+            entries:Vec::new(),
+        }
+    }
+
+    pub fn load<P:AsRef<Path>>(path:P)->GameResult<StringTable>{
+        let path=path.as_ref();
+        let text=read_to_string(path).map_err(|e|GameError::file(path,e))?;
+        Self::parse(&text).map_err(|e|GameError::file(path,e))
+    }
+
+    pub fn parse(text:&str)->Result<StringTable,Error>{
+        let mut entries=Vec::new();
+
+        for (c,line) in text.lines().enumerate(){
+            let line=line.trim();
+            if line.is_empty() || line.starts_with("//"){
+                continue
+            }
+
+            let mut split=line.splitn(2,'=');
+            let key=split.next().unwrap().trim();
+            let value=match split.next(){
+                Some(value)=>value.trim(),
+                None=>return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("строка {}: ожидается '[ключ] = [строка]'",c+1)
+                ))
+            };
+
+            entries.push((key.to_string(),value.to_string()));
+        }
+
+        entries.sort_by(|a,b|a.0.cmp(&b.0));
+
+        Ok(Self{
+            entries
+        })
+    }
+
+    pub fn get(&self,key:&str)->Option<&str>{
+        match self.entries.binary_search_by(|entry|entry.0.as_str().cmp(key)){
+            Ok(index)=>Some(self.entries[index].1.as_str()),
+            Err(_)=>None,
+        }
+    }
+
+    pub fn len(&self)->usize{
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self)->bool{
+        self.entries.is_empty()
+    }
+
+    /// Все строки таблицы.
+    pub fn values(&self)->impl Iterator<Item=&str>{
+        self.entries.iter().map(|entry|entry.1.as_str())
+    }
+
+    /// Все пары [ключ, строка] в порядке ключей.
+    pub fn entries(&self)->impl Iterator<Item=(&str,&str)>{
+        self.entries.iter().map(|entry|(entry.0.as_str(),entry.1.as_str()))
+    }
+}
+
+/// Перевод интерфейса и диалогов.
+///
+/// Языки перечисляются в разделе `languages` манифеста ресурсов:
+/// логическое имя - код языка, путь - таблица строк.
+/// Первый язык - язык по умолчанию. Если строки нет в таблице
+/// выбранного языка, то она берётся из таблицы по умолчанию,
+/// а ключ запоминается как отсутствующий.
+///
+/// Диалоги каждого языка лежат в папке `dialogues/[код языка]`.
+pub struct Localization{
+    languages:Vec<ManifestEntry>,
+    /// Номер выбранного языка
+    language:usize,
+    default:StringTable,
+    current:StringTable,
+    missing:Vec<String>,
+}
+
+impl Localization{
+    pub const fn new()->Localization{
+        Self{
+            languages:Vec::new(),
+            language:0usize,
+            default:StringTable::new(),
+            current:StringTable::new(),
+            missing:Vec::new(),
+        }
+    }
+
+    /// Загрузка таблиц строк для языка по умолчанию и выбранного.
+    ///
+    /// Если выбранного языка нет, используется язык по умолчанию.
+    pub fn load(languages:Vec<ManifestEntry>,language:&str)->GameResult<Localization>{
+        if languages.is_empty(){
+            return Err(GameError::missing("languages",language))
+        }
+
+        let default=StringTable::load(&languages[0].path)?;
+
+        let mut localization=Self{
+            languages,
+            language:0usize,
+            default,
+            current:StringTable::new(),
+            missing:Vec::new(),
+        };
+
+        if localization.set_language(language).is_err(){
+            localization.set_language_index(0)?;
+        }
+
+        Ok(localization)
+    }
+
+    /// Код выбранного языка (пустой, пока языки не загружены).
+    pub fn language(&self)->&str{
+        self.languages.get(self.language).map_or("",|entry|entry.name.as_str())
+    }
+
+    /// Код языка по умолчанию (пустой, пока языки не загружены).
+    pub fn default_language(&self)->&str{
+        self.languages.first().map_or("",|entry|entry.name.as_str())
+    }
+
+    /// Коды всех доступных языков.
+    pub fn languages(&self)->impl Iterator<Item=&str>{
+        self.languages.iter().map(|entry|entry.name.as_str())
+    }
+
+    pub fn set_language(&mut self,language:&str)->GameResult<()>{
+        match self.languages.iter().position(|entry|entry.name==language){
+            Some(index)=>self.set_language_index(index),
+            None=>Err(GameError::missing("languages",language)),
+        }
+    }
+
+    /// Переключение на следующий язык по кругу.
+    pub fn next_language(&mut self)->GameResult<()>{
+        if self.languages.is_empty(){
+            return Err(GameError::missing("languages",""))
+        }
+        let index=(self.language+1)%self.languages.len();
+        self.set_language_index(index)
+    }
+
+    fn set_language_index(&mut self,index:usize)->GameResult<()>{
+        self.current=StringTable::load(&self.languages[index].path)?;
+        log::info(format!("язык '{}'",self.languages[index].name));
+        self.language=index;
+        self.missing.clear();
+        Ok(())
+    }
+
+    /// Строка по ключу.
+    ///
+    /// Если ключа нет ни в одной таблице, возвращается сам ключ.
+    pub fn get(&mut self,key:&str)->String{
+        if let Some(line)=self.current.get(key){
+            return line.to_string()
+        }
+
+        if !self.missing.iter().any(|missing|missing==key){
+            log::warning(format!("нет строки '{}' для языка '{}'",key,self.language()));
+            self.missing.push(key.to_string());
+        }
+
+        match self.default.get(key){
+            Some(line)=>line.to_string(),
+            None=>key.to_string(),
+        }
+    }
+
+    /// Ключи, не найденные в таблице выбранного языка.
+    pub fn missing_keys(&self)->&[String]{
+        &self.missing
+    }
+
+    /// Таблица строк выбранного языка.
+    pub fn strings(&self)->&StringTable{
+        &self.current
+    }
+
+    /// Путь к диалогу на выбранном языке.
+    ///
+    /// Если перевода диалога нет, используется диалог на языке по умолчанию.
+    pub fn dialogue_path(&self,resolver:&ResourceResolver,name:&str)->PathBuf{
+        let file=format!("{}.txt",name);
+
+        let path=resolver.resolve(Path::new("dialogues").join(self.language()).join(&file));
+        if path.exists(){
+            return path
+        }
+
+        resolver.resolve(Path::new("dialogues").join(self.default_language()).join(&file))
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    fn localization()->Localization{
+        let entry=|name:&str|ManifestEntry{name:name.to_string(),path:PathBuf::from(name)};
+        Localization{
+            languages:vec![entry("ru"),entry("en")],
+            language:1usize,
+            default:StringTable::parse("exit = Выход\nback = Назад\n").unwrap(),
+            current:StringTable::parse("// только часть строк\nexit = Exit\n").unwrap(),
+            missing:Vec::new(),
+        }
+    }
+
+    #[test]
+    fn parses_string_tables(){
+        let table=StringTable::parse("b = 2\n\n// комментарий\na = 1 = один\n").unwrap();
+        assert_eq!(table.get("a"),Some("1 = один"));
+        assert_eq!(table.get("b"),Some("2"));
+        assert_eq!(table.get("c"),None);
+        assert_eq!(table.entries().map(|entry|entry.0).collect::<Vec<_>>(),vec!["a","b"]);
+
+        assert!(StringTable::parse("без равенства\n").is_err());
+    }
+
+    #[test]
+    fn missing_keys_fall_back_once(){
+        let mut localization=localization();
+        assert_eq!(localization.language(),"en");
+        assert_eq!(localization.get("exit"),"Exit");
+
+        assert_eq!(localization.get("back"),"Назад");
+        assert_eq!(localization.get("back"),"Назад");
+        // Ключа нет нигде - возвращается сам ключ
+        assert_eq!(localization.get("unknown"),"unknown");
+
+        assert_eq!(localization.missing_keys(),&["back".to_string(),"unknown".to_string()]);
+    }
+
+    #[test]
+    fn empty_localization_does_not_panic(){
+        let mut localization=Localization::new();
+        assert_eq!(localization.language(),"");
+        assert_eq!(localization.default_language(),"");
+        assert!(localization.next_language().is_err());
+        assert_eq!(localization.get("exit"),"exit");
+    }
+}
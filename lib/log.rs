@@ -0,0 +1,320 @@
+use std::{
+    backtrace::Backtrace,
+    fmt,
+    fs::{File,OpenOptions,create_dir_all,rename,remove_file},
+    io::Write,
+    panic,
+    path::{Path,PathBuf},
+    sync::Mutex,
+    time::{SystemTime,UNIX_EPOCH},
+};
+
+/// Файлы журнала, общие для всех потоков: основной журнал и дополнительные (`add_sink`).
+static log_files:Mutex<Vec<Logger>>=Mutex::new(Vec::new());
+
+/// Записи до вызова `init` (например, разбора аргументов командной строки),
+/// дописываются в журнал при запуске.
+static pending:Mutex<Vec<(Level,String)>>=Mutex::new(Vec::new());
+
+/// Наибольшее количество записей до вызова `init`
+const max_pending:usize=256usize;
+
+/// Верхняя сцена для отчёта о падении.
+static current_scene:Mutex<&'static str>=Mutex::new("");
+
+/// Уровень записи.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,PartialOrd,Ord)]
+pub enum Level{
+    Debug,
+    Info,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Level{
+    fn fmt(&self,f:&mut fmt::Formatter)->fmt::Result{
+        let name=match self{
+            Level::Debug=>"DEBUG",
+            Level::Info=>"INFO",
+            Level::Warning=>"WARN",
+            Level::Error=>"ERROR",
+        };
+        f.pad(name)
+    }
+}
+
+/// Настройки журнала.
+pub struct LogSettings{
+    /// Папка журнала и отчётов о падении
+    directory:PathBuf,
+    /// Имя файла журнала
+    name:String,
+    /// Записи ниже этого уровня пропускаются
+    level:Level,
+    /// Размер файла, после которого начинается новый файл
+    max_size:u64,
+    /// Количество старых файлов (`[имя].1`, `[имя].2`, ...)
+    files:usize,
+}
+
+impl LogSettings{
+    pub fn new<P:Into<PathBuf>>(directory:P)->LogSettings{
+        Self{
+            directory:directory.into(),
+            name:"game.log".to_string(),
+            level:if cfg!(debug_assertions){Level::Debug}else{Level::Info},
+            max_size:1024u64*1024u64,
+            files:3usize,
+        }
+    }
+
+    pub fn name<S:Into<String>>(mut self,name:S)->LogSettings{
+        self.name=name.into();
+        self
+    }
+
+    pub fn level(mut self,level:Level)->LogSettings{
+        self.level=level;
+        self
+    }
+
+    pub fn max_size(mut self,size:u64)->LogSettings{
+        self.max_size=size;
+        self
+    }
+
+    pub fn files(mut self,files:usize)->LogSettings{
+        self.files=files;
+        self
+    }
+}
+
+struct Logger{
+    settings:LogSettings,
+    file:Option<File>,
+    /// Размер текущего файла
+    size:u64,
+}
+
+impl Logger{
+    fn path(&self)->PathBuf{
+        self.settings.directory.join(&self.settings.name)
+    }
+
+    /// Сдвиг старых файлов: `[имя]` -> `[имя].1` -> `[имя].2` ...
+    fn rotate(&mut self){
+        self.file=None;
+
+        let path=self.path();
+        let numbered=|n:usize|PathBuf::from(format!("{}.{}",path.display(),n));
+
+        if self.settings.files==0{
+            let _=remove_file(&path);
+        }
+        else{
+            let _=remove_file(numbered(self.settings.files));
+            for n in (1..self.settings.files).rev(){
+                let _=rename(numbered(n),numbered(n+1));
+            }
+            let _=rename(&path,numbered(1));
+        }
+
+        self.open();
+    }
+
+    fn open(&mut self){
+        self.file=OpenOptions::new().create(true).append(true).open(self.path()).ok();
+        self.size=self.file.as_ref()
+                .and_then(|file|file.metadata().ok())
+                .map_or(0u64,|metadata|metadata.len());
+    }
+
+    fn write(&mut self,line:&str){
+        if self.size>=self.settings.max_size{
+            self.rotate();
+        }
+
+        if let Some(file)=&mut self.file{
+            if writeln!(file,"{}",line).is_ok(){
+                self.size+=line.len() as u64+1u64;
+            }
+        }
+    }
+}
+
+/// Время в секундах от начала эпохи с миллисекундами.
+fn timestamp()->String{
+    let time=SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    format!("{}.{:03}",time.as_secs(),time.subsec_millis())
+}
+
+fn start(settings:LogSettings)->Logger{
+    let _=create_dir_all(&settings.directory);
+
+    let mut logger=Logger{
+        settings,
+        file:None,
+        size:0u64,
+    };
+    logger.rotate();
+    logger
+}
+
+/// Запуск журнала.
+///
+/// Журнал прошлого запуска сдвигается в `[имя].1`.
+pub fn init(settings:LogSettings){
+    let mut logger=start(settings);
+
+    if let Ok(mut lines)=pending.lock(){
+        for (level,line) in lines.drain(..){
+            if level>=logger.settings.level{
+                logger.write(&line);
+            }
+        }
+    }
+
+    if let Ok(mut loggers)=log_files.lock(){
+        loggers.clear();
+        loggers.push(logger);
+    }
+}
+
+/// Дополнительный файл журнала со своими уровнем и сдвигом файлов
+/// (например, только ошибки рядом со снимками экрана).
+///
+/// Вызывается после `init`.
+pub fn add_sink(settings:LogSettings){
+    let logger=start(settings);
+    if let Ok(mut loggers)=log_files.lock(){
+        loggers.push(logger);
+    }
+}
+
+/// Запись в журнал.
+pub fn write(level:Level,message:&str){
+    let line=format!("[{}] {:5} {}",timestamp(),level,message);
+
+    if let Ok(mut loggers)=log_files.lock(){
+        if loggers.is_empty(){
+            if let Ok(mut lines)=pending.lock(){
+                if lines.len()<max_pending{
+                    lines.push((level,line));
+                }
+            }
+            return
+        }
+
+        for logger in loggers.iter_mut(){
+            if level>=logger.settings.level{
+                logger.write(&line);
+            }
+        }
+    }
+}
+
+pub fn debug<S:AsRef<str>>(message:S){
+    write(Level::Debug,message.as_ref())
+}
+
+pub fn info<S:AsRef<str>>(message:S){
+    write(Level::Info,message.as_ref())
+}
+
+pub fn warning<S:AsRef<str>>(message:S){
+    write(Level::Warning,message.as_ref())
+}
+
+pub fn error<S:AsRef<str>>(message:S){
+    write(Level::Error,message.as_ref())
+}
+
+/// Смена верхней сцены (для отчёта о падении).
+pub fn set_scene(name:&'static str){
+    if let Ok(mut scene)=current_scene.lock(){
+        *scene=name;
+    }
+}
+
+fn scene()->&'static str{
+    match current_scene.lock(){
+        Ok(scene)=>*scene,
+        Err(poisoned)=>*poisoned.into_inner(),
+    }
+}
+
+/// Установка обработчика паники, записывающего отчёт о падении
+/// `crash_[время].txt` с текущей сценой и стеком вызовов
+/// в папку журнала.
+///
+/// После записи вызывается прежний обработчик.
+pub fn install_panic_hook(){
+    let directory=match log_files.lock(){
+        Ok(loggers)=>loggers.first().map(|logger|logger.settings.directory.clone()),
+        Err(_)=>None,
+    };
+    let directory=directory.unwrap_or_else(||PathBuf::from("."));
+
+    let previous=panic::take_hook();
+    panic::set_hook(Box::new(move|info|{
+        let thread=std::thread::current();
+        let report=format!(
+            "Паника в потоке '{}': {}\nСцена: {}\n\n{}",
+            thread.name().unwrap_or("без имени"),
+            info,
+            scene(),
+            Backtrace::force_capture()
+        );
+
+        error(format!("паника в потоке '{}': {}",thread.name().unwrap_or("без имени"),info));
+        write_crash_report(&directory,&report);
+
+        previous(info)
+    }));
+}
+
+fn write_crash_report(directory:&Path,report:&str){
+    let time=SystemTime::now().duration_since(UNIX_EPOCH).map_or(0u64,|time|time.as_secs());
+    let path=directory.join(format!("crash_{}.txt",time));
+
+    if let Ok(mut file)=File::create(&path){
+        let _=file.write_all(report.as_bytes());
+        info(format!("отчёт о падении: {}",path.display()));
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn rotation_keeps_limited_files(){
+        let directory=std::env::temp_dir().join(format!("log_rotation_{}",std::process::id()));
+        let _=create_dir_all(&directory);
+
+        let mut logger=Logger{
+            settings:LogSettings::new(&directory).max_size(10u64).files(2usize),
+            file:None,
+            size:0u64,
+        };
+        logger.open();
+
+        for line in ["first line","second line","third line","fourth line"].iter(){
+            logger.write(line);
+        }
+
+        let read=|name:&str|std::fs::read_to_string(directory.join(name)).unwrap_or_default();
+        assert_eq!(read("game.log"),"fourth line\n");
+        assert_eq!(read("game.log.1"),"third line\n");
+        assert_eq!(read("game.log.2"),"second line\n");
+        assert!(!directory.join("game.log.3").exists());
+
+        let _=std::fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn level_names_are_padded(){
+        assert_eq!(format!("{:5}|",Level::Warning),"WARN |");
+        assert_eq!(format!("{:5}|",Level::Error),"ERROR|");
+    }
+}
@@ -0,0 +1,227 @@
+use crate::{
+    error::{GameError,GameResult},
+    log,
+    resources::ResourceResolver,
+};
+
+use std::{
+    fs::read_to_string,
+    io::{Error,ErrorKind},
+    path::{Path,PathBuf},
+};
+
+/// Путь к манифесту относительно папки ресурсов
+const manifest_path:&'static str="manifest";
+
+/// Ресурс, объявленный в манифесте.
+#[derive(Clone)]
+pub struct ManifestEntry{
+    /// Логическое имя ресурса
+    pub name:String,
+    /// Путь к файлу ресурса
+    pub path:PathBuf,
+}
+
+/// Описание всех ресурсов игры.
+///
+/// Загружается из файла `manifest` в папке ресурсов,
+/// поэтому для добавления новых файлов не требуется перекомпиляция.
+/// Пути ищутся с учётом подключённых модов (см. `ResourceResolver`).
+///
+/// Формат файла совпадает с форматом таблицы страниц:
+/// ```text
+/// [раздел]{
+///     [логическое имя] = [путь относительно папки ресурсов]
+/// }
+/// ```
+/// Разделы: `fonts`, `audio`, `wallpapers`, `characters`, `images`, `languages`.
+/// Имена внутри раздела не повторяются.
+/// Порядок шрифтов определяет их номера (первый - главный шрифт).
+/// Первый язык - язык по умолчанию.
+#[derive(Clone)]
+pub struct ResourceManifest{
+    pub fonts:Vec<ManifestEntry>,
+    pub audio:Vec<ManifestEntry>,
+    pub wallpapers:Vec<ManifestEntry>,
+    pub characters:Vec<ManifestEntry>,
+    pub images:Vec<ManifestEntry>,
+    pub languages:Vec<ManifestEntry>,
+}
+
+impl ResourceManifest{
+    pub const fn new()->ResourceManifest{
+        Self{
+            fonts:Vec::new(),
+            audio:Vec::new(),
+            wallpapers:Vec::new(),
+            characters:Vec::new(),
+            images:Vec::new(),
+            languages:Vec::new(),
+        }
+    }
+
+    /// Загрузка манифеста.
+    pub fn load(resolver:&ResourceResolver)->GameResult<ResourceManifest>{
+        let path=resolver.resolve(manifest_path);
+        log::info(format!("манифест {}",path.display()));
+        let text=read_to_string(&path).map_err(|e|GameError::file(&path,e))?;
+
+        Self::parse(&text,resolver).map_err(|e|GameError::file(&path,e))
+    }
+
+    /// Разбор текста манифеста.
+    pub fn parse(text:&str,resolver:&ResourceResolver)->Result<ResourceManifest,Error>{
+        let mut manifest=ResourceManifest::new();
+
+        // Текущий раздел
+        let mut section:Option<&mut Vec<ManifestEntry>>=None;
+
+        for (c,line) in text.lines().enumerate(){
+            let line=line.trim();
+            // Пропуск пустых строк и комментариев
+            if line.is_empty() || line.starts_with("//"){
+                continue
+            }
+
+            if line=="}"{
+                if section.take().is_none(){
+                    return Err(manifest_error(c,"лишняя закрывающая скобка"))
+                }
+                continue
+            }
+
+            if line.ends_with('{'){
+                if section.is_some(){
+                    return Err(manifest_error(c,"вложенные разделы не поддерживаются"))
+                }
+
+                let name=line.trim_end_matches('{').trim();
+                section=Some(match name{
+                    "fonts"=>&mut manifest.fonts,
+                    "audio"=>&mut manifest.audio,
+                    "wallpapers"=>&mut manifest.wallpapers,
+                    "characters"=>&mut manifest.characters,
+                    "images"=>&mut manifest.images,
+                    "languages"=>&mut manifest.languages,
+                    _=>return Err(manifest_error(c,&format!("неизвестный раздел '{}'",name)))
+                });
+                continue
+            }
+
+            let entries=match &mut section{
+                Some(entries)=>entries,
+                None=>return Err(manifest_error(c,"ресурс вне раздела")),
+            };
+
+            let mut split=line.splitn(2,'=');
+            let name=split.next().unwrap().trim();
+            let file=match split.next(){
+                Some(file)=>file.trim(),
+                None=>return Err(manifest_error(c,"ожидается '[имя] = [путь]'")),
+            };
+
+            if name.is_empty() || file.is_empty(){
+                return Err(manifest_error(c,"пустое имя или путь"))
+            }
+
+            if entries.iter().any(|entry|entry.name==name){
+                return Err(manifest_error(c,&format!("повторное имя '{}'",name)))
+            }
+
+            entries.push(ManifestEntry{
+                name:name.to_string(),
+                path:resolver.resolve(file),
+            });
+        }
+
+        if section.is_some(){
+            return Err(manifest_error(text.lines().count().saturating_sub(1),"раздел не закрыт"))
+        }
+
+        Ok(manifest)
+    }
+
+    /// Номер шрифта по имени.
+    pub fn font_index(&self,name:&str)->Option<usize>{
+        self.fonts.iter().position(|entry|entry.name==name)
+    }
+
+    pub fn track(&self,name:&str)->Option<&Path>{
+        find(&self.audio,name)
+    }
+
+    pub fn wallpaper(&self,name:&str)->Option<&Path>{
+        find(&self.wallpapers,name)
+    }
+
+    pub fn character(&self,name:&str)->Option<&Path>{
+        find(&self.characters,name)
+    }
+
+    pub fn image(&self,name:&str)->Option<&Path>{
+        find(&self.images,name)
+    }
+
+    /// Путь к обязательным обоям.
+    pub fn require_wallpaper(&self,name:&str)->GameResult<&Path>{
+        self.wallpaper(name).ok_or_else(||GameError::missing("wallpapers",name))
+    }
+
+    /// Путь к обязательному изображению.
+    pub fn require_image(&self,name:&str)->GameResult<&Path>{
+        self.image(name).ok_or_else(||GameError::missing("images",name))
+    }
+}
+
+fn find<'a>(entries:&'a [ManifestEntry],name:&str)->Option<&'a Path>{
+    entries.iter().find(|entry|entry.name==name).map(|entry|entry.path.as_path())
+}
+
+fn manifest_error(line:usize,message:&str)->Error{
+    Error::new(ErrorKind::InvalidData,format!("manifest, строка {}: {}",line+1,message))
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn parses_sections_and_comments(){
+        let text="\
+// Шрифты
+fonts {
+    main = fonts/main.font
+    // второй шрифт
+    dialogue = fonts/dialogue.font
+}
+
+images {
+    cursor = images/cursor.png
+}
+";
+        let resolver=ResourceResolver::new();
+        let manifest=ResourceManifest::parse(text,&resolver).unwrap();
+
+        assert_eq!(manifest.fonts.len(),2usize);
+        assert_eq!(manifest.font_index("dialogue"),Some(1usize));
+        assert_eq!(manifest.image("cursor"),Some(Path::new("resources/images/cursor.png")));
+        assert!(manifest.audio.is_empty());
+        assert!(manifest.require_wallpaper("main_menu").is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_manifests(){
+        let resolver=ResourceResolver::new();
+        let error=|text:&str|ResourceManifest::parse(text,&resolver).err().map(|e|e.to_string()).unwrap_or_default();
+
+        assert!(error("sounds {\n}\n").contains("неизвестный раздел 'sounds'"));
+        assert!(error("fonts {\n    main = a.font\n    main = b.font\n}\n").contains("строка 3: повторное имя 'main'"));
+        assert!(error("main = a.font\n").contains("ресурс вне раздела"));
+        assert!(error("fonts {\n    main\n}\n").contains("ожидается"));
+        assert!(error("fonts {\n").contains("раздел не закрыт"));
+        assert!(error("}\n").contains("лишняя закрывающая скобка"));
+
+        // Одинаковые имена в разных разделах допустимы
+        assert!(ResourceManifest::parse("fonts {\n    main = a\n}\nimages {\n    main = b\n}\n",&resolver).is_ok());
+    }
+}
@@ -0,0 +1,324 @@
+use cat_engine::{
+    Colour,
+    graphics::{
+        Graphics,
+        Graphics2D,
+    },
+    shapes::Rectangle,
+    text::{Scale,TextBase},
+    texture::{Texture,ImageObject},
+    glium::Surface,
+};
+
+use std::cell::RefCell;
+
+/// Виды объектов
+#[derive(Clone,Copy)]
+enum ObjectKind{
+    Simple,
+    Text,
+    Textured,
+    Texture,
+}
+
+const object_kinds:[ObjectKind;4]=[
+    ObjectKind::Simple,
+    ObjectKind::Text,
+    ObjectKind::Textured,
+    ObjectKind::Texture,
+];
+
+/// Учёт графических объектов.
+///
+/// `Graphics2D` удаляет объекты только с конца, поэтому объекты каждого вида
+/// образуют стек: освобождённый объект удаляется, когда над ним не остаётся занятых.
+/// До этого он просто не отрисовывается и занимает место в буфере.
+struct Storage{
+    /// Количество объектов каждого вида
+    counts:[usize;4],
+    /// Освобождённые, но ещё не удалённые объекты
+    released:[Vec<usize>;4],
+}
+
+impl Storage{
+    const fn new()->Storage{
+        Self{
+            counts:[0usize;4],
+            released:[Vec::new(),Vec::new(),Vec::new(),Vec::new()],
+        }
+    }
+
+    fn register(&mut self,kind:ObjectKind,index:usize){
+        let count=&mut self.counts[kind as usize];
+        if *count<=index{
+            *count=index+1;
+        }
+    }
+
+    fn release(&mut self,kind:ObjectKind,index:usize){
+        self.released[kind as usize].push(index)
+    }
+
+    /// Количество освобождённых объектов на вершине стека,
+    /// которые можно удалить с конца.
+    fn take_removable(&mut self,kind:ObjectKind)->usize{
+        let kind=kind as usize;
+        let mut removable=0usize;
+
+        while self.counts[kind]>0{
+            let last=self.counts[kind]-1;
+
+            let released=&mut self.released[kind];
+            match released.iter().position(|&index|index==last){
+                Some(position)=>{
+                    released.swap_remove(position);
+                }
+                None=>break,
+            }
+
+            self.counts[kind]=last;
+            removable+=1;
+        }
+
+        removable
+    }
+}
+
+thread_local!{
+    static object_storage:RefCell<Storage>=RefCell::new(Storage::new());
+}
+
+fn register(kind:ObjectKind,index:usize){
+    object_storage.with(|storage|storage.borrow_mut().register(kind,index))
+}
+
+fn release(kind:ObjectKind,index:usize){
+    object_storage.with(|storage|storage.borrow_mut().release(kind,index))
+}
+
+/// Удаление освобождённых объектов с вершин стеков.
+///
+/// Вызывается автоматически перед созданием каждого объекта
+/// и стеком сцен после удаления сцены.
+pub fn collect(graphics:&mut Graphics2D){
+    object_storage.with(|storage|{
+        let mut storage=storage.borrow_mut();
+
+        for &object_kind in object_kinds.iter(){
+            for _ in 0..storage.take_removable(object_kind){
+                match object_kind{
+                    ObjectKind::Simple=>graphics.remove_last_simple_object(),
+                    ObjectKind::Text=>graphics.remove_last_text_object(),
+                    ObjectKind::Textured=>graphics.remove_last_textured_object(),
+                    ObjectKind::Texture=>graphics.remove_last_texture(),
+                }
+            }
+        }
+    })
+}
+
+/// Простой объект (фигура), удаляется вместе с владельцем.
+///
+/// Место в буфере освобождается, только когда удалены
+/// и все простые объекты, созданные после этого.
+pub struct SimpleObject{
+    index:usize,
+}
+
+impl SimpleObject{
+    pub fn new(rectangle:&Rectangle,graphics:&mut Graphics2D)->Option<SimpleObject>{
+        collect(graphics);
+        let index=graphics.add_simple_object(rectangle)?;
+        register(ObjectKind::Simple,index);
+        Some(Self{index})
+    }
+
+    pub fn colour<'a>(&self,graphics:&'a mut Graphics2D)->&'a mut Colour{
+        graphics.get_simple_object_colour(self.index)
+    }
+
+    pub fn draw<S:Surface>(&self,graphics:&mut Graphics<S>){
+        graphics.draw_simple_object(self.index).unwrap();
+    }
+
+    pub fn draw_shift<S:Surface>(&self,shift:[f32;2],graphics:&mut Graphics<S>){
+        graphics.draw_shift_simple_object(self.index,shift).unwrap();
+    }
+}
+
+impl Drop for SimpleObject{
+    fn drop(&mut self){
+        release(ObjectKind::Simple,self.index)
+    }
+}
+
+/// Текстовый объект, удаляется вместе с владельцем.
+///
+/// Как и остальные объекты, освобождается в порядке, обратном созданию.
+pub struct TextObject{
+    index:usize,
+}
+
+impl TextObject{
+    pub fn new(text:String,base:&TextBase,font:usize,graphics:&mut Graphics2D)->Option<TextObject>{
+        collect(graphics);
+        let index=graphics.add_text_object(text,base,font)?;
+        register(ObjectKind::Text,index);
+        Some(Self{index})
+    }
+
+    pub fn text<'a>(&self,graphics:&'a mut Graphics2D)->&'a mut String{
+        graphics.get_text_object_text(self.index)
+    }
+
+    pub fn font(&self,graphics:&mut Graphics2D)->usize{
+        *graphics.get_text_object_font(self.index)
+    }
+
+    pub fn scale(&self,graphics:&mut Graphics2D)->Scale{
+        *graphics.get_text_object_scale(self.index)
+    }
+
+    pub fn position<'a>(&self,graphics:&'a mut Graphics2D)->&'a mut [f32;2]{
+        graphics.get_text_object_position(self.index)
+    }
+
+    pub fn colour<'a>(&self,graphics:&'a mut Graphics2D)->&'a mut Colour{
+        graphics.get_text_object_colour(self.index)
+    }
+
+    pub fn draw<S:Surface>(&self,graphics:&mut Graphics<S>){
+        graphics.draw_text_object(self.index).unwrap();
+    }
+
+    pub fn draw_shift<S:Surface>(&self,shift:[f32;2],graphics:&mut Graphics<S>){
+        graphics.draw_shift_text_object(self.index,shift).unwrap();
+    }
+}
+
+impl Drop for TextObject{
+    fn drop(&mut self){
+        release(ObjectKind::Text,self.index)
+    }
+}
+
+/// Текстура, удаляется вместе с владельцем.
+///
+/// Текстура не должна переживать текстурные объекты, которые её используют.
+/// Память текстуры освобождается после удаления всех текстур, загруженных позже.
+pub struct TextureObject{
+    index:usize,
+}
+
+impl TextureObject{
+    pub fn new(texture:Texture,graphics:&mut Graphics2D)->TextureObject{
+        collect(graphics);
+        let index=graphics.add_texture(texture);
+        register(ObjectKind::Texture,index);
+        Self{index}
+    }
+}
+
+impl Drop for TextureObject{
+    fn drop(&mut self){
+        release(ObjectKind::Texture,self.index)
+    }
+}
+
+/// Текстурный объект, удаляется вместе с владельцем.
+///
+/// Долгоживущие объекты стоит создавать раньше временных:
+/// объект, созданный до ещё занятого, остаётся в буфере до его удаления.
+pub struct TexturedObject{
+    index:usize,
+}
+
+impl TexturedObject{
+    pub fn new(image:&ImageObject,texture:&TextureObject,graphics:&mut Graphics2D)->Option<TexturedObject>{
+        collect(graphics);
+        let index=graphics.add_textured_object(image,texture.index)?;
+        register(ObjectKind::Textured,index);
+        Some(Self{index})
+    }
+
+    pub fn texture<'a>(&self,graphics:&'a mut Graphics2D)->&'a mut Texture{
+        graphics.get_textured_object_texture(self.index)
+    }
+
+    pub fn colour<'a>(&self,graphics:&'a mut Graphics2D)->&'a mut Colour{
+        graphics.get_textured_object_colour(self.index)
+    }
+
+    /// Замена вершин (положения и текстурных координат).
+    pub fn rewrite_vertices(&self,image:&ImageObject,graphics:&mut Graphics2D){
+        graphics.rewrite_textured_object_vertices(self.index,&image.vertices());
+    }
+
+    pub fn draw<S:Surface>(&self,graphics:&mut Graphics<S>){
+        graphics.draw_textured_object(self.index).unwrap();
+    }
+
+    pub fn draw_shift<S:Surface>(&self,shift:[f32;2],graphics:&mut Graphics<S>){
+        graphics.draw_shift_textured_object(self.index,shift).unwrap();
+    }
+
+    pub fn draw_rotate<S:Surface>(&self,center:[f32;2],angle:f32,graphics:&mut Graphics<S>){
+        graphics.draw_rotate_textured_object(self.index,center,angle).unwrap();
+    }
+
+    /// Поворот на угол `angle` вокруг начала координат и сдвиг.
+    ///
+    /// Поворот вокруг точки `c` и есть такой сдвиг при `c=(I-R)⁻¹·shift`,
+    /// поэтому хватает одной отрисовки с поворотом.
+    pub fn draw_shift_rotate<S:Surface>(&self,shift:[f32;2],angle:f32,graphics:&mut Graphics<S>){
+        let (sin,cos)=angle.sin_cos();
+        let determinant=2f32-2f32*cos;
+
+        if determinant<1e-6f32{
+            self.draw_shift(shift,graphics);
+        }
+        else{
+            let center=[
+                ((1f32-cos)*shift[0]-sin*shift[1])/determinant,
+                (sin*shift[0]+(1f32-cos)*shift[1])/determinant,
+            ];
+            self.draw_rotate(center,angle,graphics);
+        }
+    }
+}
+
+impl Drop for TexturedObject{
+    fn drop(&mut self){
+        release(ObjectKind::Textured,self.index)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn released_objects_are_removed_from_the_top(){
+        let mut storage=Storage::new();
+        for index in 0..3usize{
+            storage.register(ObjectKind::Textured,index);
+        }
+
+        // Средний объект остаётся в буфере, пока занят верхний
+        storage.release(ObjectKind::Textured,1usize);
+        assert_eq!(storage.take_removable(ObjectKind::Textured),0usize);
+        assert_eq!(storage.counts[ObjectKind::Textured as usize],3usize);
+
+        storage.release(ObjectKind::Textured,2usize);
+        assert_eq!(storage.take_removable(ObjectKind::Textured),2usize);
+        assert_eq!(storage.counts[ObjectKind::Textured as usize],1usize);
+
+        // Другие виды объектов не затрагиваются
+        storage.register(ObjectKind::Simple,0usize);
+        assert_eq!(storage.take_removable(ObjectKind::Simple),0usize);
+
+        storage.release(ObjectKind::Textured,0usize);
+        assert_eq!(storage.take_removable(ObjectKind::Textured),1usize);
+        assert!(storage.released[ObjectKind::Textured as usize].is_empty());
+    }
+}
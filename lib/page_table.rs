@@ -0,0 +1,283 @@
+use crate::{
+    error::{GameError,GameResult},
+    player_name::{
+        NameSubstitution,
+        player_placeholder,
+        empty_speaker,
+    },
+};
+
+use std::{
+    fs::read_to_string,
+    io::{Error,ErrorKind},
+    path::Path,
+};
+
+/// Страница игры.
+#[derive(Clone,Debug,PartialEq)]
+pub struct PageEntry{
+    pub name:String,
+    /// Обои из раздела `wallpapers` манифеста
+    pub wallpaper:String,
+    /// Имя файла диалога без расширения
+    pub dialogue:String,
+}
+
+/// Таблица страниц игры в порядке прохождения.
+///
+/// Формат файла:
+/// ```text
+/// [имя страницы] - {
+///     wallpaper = [обои]
+///     dialogue = [диалог]
+/// }
+/// ```
+pub struct PageTable{
+    pages:Vec<PageEntry>,
+}
+
+impl PageTable{
+    pub const fn new()->PageTable{
+        Self{
+            pages:Vec::new(),
+        }
+    }
+
+    pub fn load<P:AsRef<Path>>(path:P)->GameResult<PageTable>{
+        let path=path.as_ref();
+        let text=read_to_string(path).map_err(|e|GameError::file(path,e))?;
+        Self::parse(&text).map_err(|e|GameError::file(path,e))
+    }
+
+    pub fn parse(text:&str)->Result<PageTable,Error>{
+        let mut pages=Vec::new();
+        let mut page:Option<PageEntry>=None;
+
+        for (c,line) in text.lines().enumerate(){
+            let line=line.trim();
+            if line.is_empty() || line.starts_with("//"){
+                continue
+            }
+
+            if line=="}"{
+                match page.take(){
+                    Some(entry)=>{
+                        if entry.wallpaper.is_empty() || entry.dialogue.is_empty(){
+                            return Err(page_table_error(c,"у страницы нет обоев или диалога"))
+                        }
+                        pages.push(entry)
+                    }
+                    None=>return Err(page_table_error(c,"лишняя закрывающая скобка")),
+                }
+                continue
+            }
+
+            if line.ends_with('{'){
+                if page.is_some(){
+                    return Err(page_table_error(c,"вложенные страницы не поддерживаются"))
+                }
+
+                let name=line.trim_end_matches('{').trim().trim_end_matches('-').trim();
+                page=Some(PageEntry{
+                    name:name.to_string(),
+                    wallpaper:String::new(),
+                    dialogue:String::new(),
+                });
+                continue
+            }
+
+            let entry=match &mut page{
+                Some(entry)=>entry,
+                None=>return Err(page_table_error(c,"параметр вне страницы")),
+            };
+
+            let mut split=line.splitn(2,'=');
+            let key=split.next().unwrap().trim();
+            let value=match split.next(){
+                Some(value)=>value.trim().to_string(),
+                None=>return Err(page_table_error(c,"ожидается '[параметр] = [значение]'")),
+            };
+
+            match key{
+                "wallpaper"=>entry.wallpaper=value,
+                "dialogue"=>entry.dialogue=value,
+                _=>return Err(page_table_error(c,&format!("неизвестный параметр '{}'",key))),
+            }
+        }
+
+        if page.is_some(){
+            return Err(page_table_error(text.lines().count().saturating_sub(1),"страница не закрыта"))
+        }
+
+        Ok(Self{
+            pages,
+        })
+    }
+
+    pub fn len(&self)->usize{
+        self.pages.len()
+    }
+
+    pub fn is_empty(&self)->bool{
+        self.pages.is_empty()
+    }
+
+    pub fn get(&self,index:usize)->Option<&PageEntry>{
+        self.pages.get(index)
+    }
+
+    pub fn pages(&self)->&[PageEntry]{
+        &self.pages
+    }
+}
+
+/// Имена персонажей из заголовка диалога:
+/// ```text
+/// {
+///     [сокращение] = [персонаж] ([положение])
+/// }
+/// ```
+pub fn dialogue_characters(text:&str)->Vec<String>{
+    let mut characters=Vec::new();
+    let mut lines=text.lines().map(str::trim).filter(|line|!line.is_empty());
+
+    if lines.next()!=Some("{"){
+        return characters
+    }
+
+    for line in lines{
+        if line=="}"{
+            break
+        }
+
+        if let Some(value)=line.splitn(2,'=').nth(1){
+            let name=match value.find('('){
+                Some(position)=>&value[..position],
+                None=>value,
+            };
+            let name=name.trim();
+            if !name.is_empty() && !characters.iter().any(|character|character==name){
+                characters.push(name.to_string());
+            }
+        }
+    }
+
+    characters
+}
+
+/// Реплика диалога, готовая к выводу на экран.
+#[derive(Clone,Debug,PartialEq)]
+pub struct DialogueLine{
+    /// Имя говорящего (пустое для мыслей и предыстории)
+    pub speaker:String,
+    pub text:String,
+}
+
+/// Реплики диалога с подставленными именами:
+/// ```text
+/// [краткое имя] - [однострочный диалог]
+/// ```
+/// Краткие имена берутся из заголовка, `{}` и `_` заменяются по правилам `NameSubstitution`.
+pub fn dialogue_lines(text:&str,substitution:&NameSubstitution)->Result<Vec<DialogueLine>,Error>{
+    let mut speakers=Vec::new();
+    let mut dialogue=Vec::new();
+    let mut header=false;
+
+    for (c,line) in text.lines().enumerate(){
+        let line=line.trim();
+        if line.is_empty(){
+            continue
+        }
+
+        if header{
+            if line=="}"{
+                header=false;
+            }
+            else if let Some((short,value))=split_once(line,'='){
+                let name=match value.find('('){
+                    Some(position)=>&value[..position],
+                    None=>value,
+                };
+                // Дополнительная черта нужна только для выбора текстуры
+                let name=name.trim().split('.').next().unwrap_or("");
+                speakers.push((short.to_string(),name.to_string()));
+            }
+            continue
+        }
+
+        if line=="{" && speakers.is_empty() && dialogue.is_empty(){
+            header=true;
+            continue
+        }
+
+        let (short,text)=match split_once(line,'-'){
+            Some(split)=>split,
+            None=>return Err(dialogue_error(c,"ожидается '[краткое имя] - [реплика]'")),
+        };
+
+        let speaker=if short==player_placeholder || short==empty_speaker{
+            substitution.speaker(short)
+        }
+        else{
+            match speakers.iter().find(|(name,_)|name==short){
+                Some((_,name))=>name.as_str(),
+                None=>return Err(dialogue_error(c,&format!("неизвестный персонаж '{}'",short))),
+            }
+        };
+
+        dialogue.push(DialogueLine{
+            speaker:speaker.to_string(),
+            text:substitution.line(text),
+        });
+    }
+
+    Ok(dialogue)
+}
+
+fn split_once(line:&str,separator:char)->Option<(&str,&str)>{
+    let mut split=line.splitn(2,separator);
+    let key=split.next()?.trim();
+    let value=split.next()?.trim();
+    Some((key,value))
+}
+
+fn dialogue_error(line:usize,message:&str)->Error{
+    Error::new(ErrorKind::InvalidData,format!("диалог, строка {}: {}",line+1,message))
+}
+
+fn page_table_error(line:usize,message:&str)->Error{
+    Error::new(ErrorKind::InvalidData,format!("page_table, строка {}: {}",line+1,message))
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn parses_pages_and_dialogue_characters(){
+        let table=PageTable::parse("Начало - {\n    wallpaper = Общага\n    dialogue = Начало\n}\n").unwrap();
+        assert_eq!(table.len(),1usize);
+        assert_eq!(table.get(0).unwrap().wallpaper,"Общага");
+        assert_eq!(table.get(0).unwrap().name,"Начало");
+
+        let characters=dialogue_characters("{\n    К = Константин Анатольевич.0 (CenterRight)\n    Л = Лера (Left)\n}\nЛ - Привет.\n");
+        assert_eq!(characters,vec!["Константин Анатольевич.0".to_string(),"Лера".to_string()]);
+    }
+
+    #[test]
+    fn substitutes_names_in_dialogue_lines(){
+        let substitution=NameSubstitution::new("Алиса","Я");
+        let text="{\n    К = Константин Анатольевич.0 (CenterRight)\n}\n_ - Утро.\n{} - Здравствуйте.\nК - Здрасьте, {}.\n";
+
+        let lines=dialogue_lines(text,&substitution).unwrap();
+        let line=|speaker:&str,text:&str|DialogueLine{speaker:speaker.to_string(),text:text.to_string()};
+        assert_eq!(lines,vec![
+            line("","Утро."),
+            line("Я","Здравствуйте."),
+            line("Константин Анатольевич","Здрасьте, Алиса."),
+        ]);
+
+        assert!(dialogue_lines("Л - Привет.\n",&substitution).is_err());
+        assert!(dialogue_lines("без говорящего\n",&substitution).is_err());
+    }
+}
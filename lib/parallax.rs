@@ -0,0 +1,193 @@
+/// Слой параллакса - номер глубины в порядке добавления в `ParallaxSettings`.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct ParallaxLayer(pub usize);
+
+/// Настройки параллакса.
+pub struct ParallaxSettings{
+    /// Глубины слоёв: сдвиг слоя - отклонение курсора
+    /// от центра окна, делённое на глубину
+    depths:Vec<f32>,
+    /// Жёсткость пружины, тянущей сдвиг к курсору,
+    /// в радианах в секунду (0 - без сглаживания)
+    stiffness:f32,
+}
+
+impl ParallaxSettings{
+    pub fn new()->ParallaxSettings{
+        Self{
+            depths:Vec::new(),
+            stiffness:12f32,
+        }
+    }
+
+    /// Добавление слоя с глубиной `depth`
+    /// (чем больше глубина, тем меньше слой сдвигается).
+    ///
+    /// Глубина должна быть положительной - на неё делится сдвиг.
+    pub fn layer(mut self,depth:f32)->ParallaxSettings{
+        assert!(depth>0f32,"глубина слоя параллакса должна быть положительной: {}",depth);
+        self.depths.push(depth);
+        self
+    }
+
+    /// Жёсткость пружины: чем больше, тем быстрее слои догоняют курсор
+    /// (около `4/stiffness` секунд до остановки).
+    pub fn stiffness(mut self,stiffness:f32)->ParallaxSettings{
+        self.stiffness=stiffness.max(0f32);
+        self
+    }
+}
+
+/// Сдвиг слоёв за курсором мыши.
+///
+/// Отклонение курсора от центра окна сглаживается и пересчитывается
+/// в сдвиги слоёв один раз за кадр (`update`), поэтому отрисовка
+/// и проверка попадания курсора используют одни и те же сдвиги.
+///
+/// Сглаживание - критически демпфированная пружина: сдвиг плавно
+/// догоняет курсор без перелёта, а дробные доли пикселя накапливаются
+/// между кадрами, поэтому при медленном движении мыши слои
+/// не перескакивают вслед за целыми пикселями курсора.
+pub struct Parallax{
+    depths:Vec<f32>,
+    stiffness:f32,
+    /// Выключенный параллакс плавно возвращает слои на место
+    enabled:bool,
+    /// Сглаженное отклонение курсора от центра окна
+    offset:[f32;2],
+    /// Скорость изменения отклонения в пикселях в секунду
+    velocity:[f32;2],
+    /// Сдвиги слоёв за текущий кадр
+    shifts:Vec<[f32;2]>,
+}
+
+impl Parallax{
+    pub fn new(settings:ParallaxSettings)->Parallax{
+        Self{
+            shifts:vec![[0f32;2];settings.depths.len()],
+            depths:settings.depths,
+            stiffness:settings.stiffness,
+            enabled:true,
+            offset:[0f32;2],
+            velocity:[0f32;2],
+        }
+    }
+
+    pub fn enabled(&self)->bool{
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self,enabled:bool){
+        self.enabled=enabled;
+    }
+
+    /// Пересчёт сдвигов слоёв.
+    ///
+    /// `target` - отклонение курсора от центра окна.
+    pub fn update(&mut self,elapsed:f32,target:[f32;2]){
+        let target=if self.enabled{target}else{[0f32;2]};
+
+        if self.stiffness>0f32{
+            // Точное решение уравнения пружины за шаг `elapsed`:
+            // x(t)=target+(a+b·t)·e^(-ωt), где a=x₀-target, b=v₀+ω·a
+            let omega=self.stiffness;
+            let decay=(-omega*elapsed).exp();
+            for c in 0..2{
+                let a=self.offset[c]-target[c];
+                let b=self.velocity[c]+omega*a;
+                self.offset[c]=target[c]+(a+b*elapsed)*decay;
+                self.velocity[c]=(b-omega*(a+b*elapsed))*decay;
+            }
+        }
+        else{
+            self.offset=target;
+            self.velocity=[0f32;2];
+        }
+
+        self.update_shifts();
+    }
+
+    /// Переход к отклонению `target` без сглаживания.
+    pub fn reset(&mut self,target:[f32;2]){
+        self.offset=if self.enabled{target}else{[0f32;2]};
+        self.velocity=[0f32;2];
+        self.update_shifts();
+    }
+
+    /// Сдвиг слоя за текущий кадр.
+    pub fn shift(&self,layer:ParallaxLayer)->[f32;2]{
+        self.shifts.get(layer.0).copied().unwrap_or([0f32;2])
+    }
+
+    /// Сдвиг слоя вместе с дополнительным сдвигом
+    /// (например, сдвигом страницы при переходе).
+    pub fn shift_with(&self,layer:ParallaxLayer,shift:[f32;2])->[f32;2]{
+        let [dx,dy]=self.shift(layer);
+        [dx+shift[0],dy+shift[1]]
+    }
+
+    /// Точка окна в координатах несдвинутого слоя
+    /// (для проверки попадания курсора в объекты слоя).
+    pub fn to_layer(&self,layer:ParallaxLayer,[x,y]:[f32;2])->[f32;2]{
+        let [dx,dy]=self.shift(layer);
+        [x-dx,y-dy]
+    }
+
+    /// Точка несдвинутого слоя в координатах окна.
+    pub fn to_window(&self,layer:ParallaxLayer,[x,y]:[f32;2])->[f32;2]{
+        let [dx,dy]=self.shift(layer);
+        [x+dx,y+dy]
+    }
+
+    fn update_shifts(&mut self){
+        let [x,y]=self.offset;
+        for (shift,depth) in self.shifts.iter_mut().zip(self.depths.iter()){
+            *shift=[x/depth,y/depth];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn layers_follow_target_and_invert(){
+        let mut parallax=Parallax::new(ParallaxSettings::new().layer(10f32).layer(20f32).stiffness(10f32));
+        let far=ParallaxLayer(1);
+
+        // Сглаживание - за один кадр слой не доходит до цели
+        parallax.update(0.016f32,[200f32,-100f32]);
+        assert!(parallax.shift(ParallaxLayer(0))[0]<20f32);
+
+        // Пружина подходит к цели без перелёта
+        for _ in 0..200{
+            parallax.update(0.016f32,[200f32,-100f32]);
+            assert!(parallax.shift(far)[0]<=10f32+0.001f32);
+        }
+        let [dx,dy]=parallax.shift(far);
+        assert!((dx-10f32).abs()<0.01f32 && (dy+5f32).abs()<0.01f32);
+
+        // Выключенный параллакс возвращает слои на место
+        parallax.set_enabled(false);
+        for _ in 0..200{
+            parallax.update(0.016f32,[200f32,-100f32]);
+        }
+        assert!(parallax.shift(far)[0].abs()<0.01f32);
+
+        let [x,y]=parallax.to_window(far,parallax.to_layer(far,[300f32,400f32]));
+        assert!((x-300f32).abs()<0.001f32 && (y-400f32).abs()<0.001f32);
+    }
+
+    #[test]
+    #[should_panic]
+    fn rejects_zero_depth(){
+        ParallaxSettings::new().layer(0f32);
+    }
+
+    #[test]
+    #[should_panic]
+    fn rejects_negative_depth(){
+        ParallaxSettings::new().layer(-1f32);
+    }
+}
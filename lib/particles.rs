@@ -0,0 +1,458 @@
+use crate::{
+    colours::White,
+    objects::{TextureObject,TexturedObject},
+};
+
+use cat_engine::{
+    Colour,
+    graphics::{Graphics,Graphics2D},
+    texture::ImageObject,
+    glium::Surface,
+};
+
+/// Простой генератор псевдослучайных чисел (SplitMix64).
+///
+/// Одно и то же зерно даёт одну и ту же последовательность.
+#[derive(Clone,Debug)]
+pub struct Random{
+    state:u64,
+}
+
+impl Random{
+    pub const fn new(seed:u64)->Random{
+        Self{
+            state:seed,
+        }
+    }
+
+    pub fn next_u64(&mut self)->u64{
+        self.state=self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z=self.state;
+        z=(z^(z>>30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z=(z^(z>>27)).wrapping_mul(0x94D049BB133111EB);
+        z^(z>>31)
+    }
+
+    /// Число от 0 до 1 (не включая 1).
+    pub fn next_f32(&mut self)->f32{
+        (self.next_u64()>>40) as f32/(1u64<<24) as f32
+    }
+
+    /// Число из промежутка [min,max].
+    pub fn range(&mut self,[min,max]:[f32;2])->f32{
+        min+(max-min)*self.next_f32()
+    }
+}
+
+/// Готовые эффекты частиц.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum ParticlePreset{
+    Rain,
+    Snow,
+    Petals,
+}
+
+/// Настройки источника частиц.
+///
+/// Промежутки задаются как [min,max], время - в секундах,
+/// расстояния - в пикселях.
+#[derive(Clone,Debug)]
+pub struct EmitterSettings{
+    /// Область появления частиц [x,y,width,height]
+    area:[f32;4],
+    /// Область, за пределами которой частицы удаляются
+    bounds:Option<[f32;4]>,
+    /// Частиц в секунду
+    rate:f32,
+    max_particles:usize,
+    velocity_x:[f32;2],
+    velocity_y:[f32;2],
+    acceleration_x:[f32;2],
+    acceleration_y:[f32;2],
+    lifetime:[f32;2],
+    /// Начальный поворот в радианах
+    rotation:[f32;2],
+    /// Скорость поворота в радианах в секунду
+    angular_velocity:[f32;2],
+    /// Размер частицы [width,height]
+    size:[f32;2],
+    /// Текстурные координаты [x1,y1,x2,y2] (изображение в атласе)
+    uv:[f32;4],
+    colour:Colour,
+    seed:u64,
+}
+
+impl EmitterSettings{
+    pub fn new(area:[f32;4])->EmitterSettings{
+        Self{
+            area,
+            bounds:None,
+            rate:1f32,
+            max_particles:100usize,
+            velocity_x:[0f32;2],
+            velocity_y:[0f32;2],
+            acceleration_x:[0f32;2],
+            acceleration_y:[0f32;2],
+            lifetime:[1f32;2],
+            rotation:[0f32;2],
+            angular_velocity:[0f32;2],
+            size:[10f32;2],
+            uv:[0f32,0f32,1f32,1f32],
+            colour:White,
+            seed:0u64,
+        }
+    }
+
+    /// Готовый эффект.
+    ///
+    /// `screen` - размер экрана [width,height].
+    pub fn preset(preset:ParticlePreset,screen:[f32;2])->EmitterSettings{
+        let [width,height]=screen;
+        let area=[0f32,-50f32,width,0f32];
+        let bounds=[-100f32,-100f32,width+200f32,height+200f32];
+
+        let settings=match preset{
+            ParticlePreset::Rain=>EmitterSettings::new(area)
+                .rate(120f32)
+                .max_particles(400usize)
+                .velocity([-40f32,-20f32],[900f32,1100f32])
+                .lifetime([2f32,2f32])
+                .size([2f32,24f32])
+                .colour([0.7f32,0.75f32,0.9f32,0.6f32]),
+
+            ParticlePreset::Snow=>EmitterSettings::new(area)
+                .rate(25f32)
+                .max_particles(300usize)
+                .velocity([-20f32,20f32],[40f32,80f32])
+                .acceleration([-10f32,10f32],[0f32,5f32])
+                .lifetime([20f32,30f32])
+                .angular_velocity([-1f32,1f32])
+                .size([8f32,8f32]),
+
+            ParticlePreset::Petals=>EmitterSettings::new(area)
+                .rate(0.25f32)
+                .max_particles(20usize)
+                .velocity([-30f32,30f32],[20f32,40f32])
+                .acceleration([-8f32,8f32],[1f32,4f32])
+                .lifetime([40f32,60f32])
+                .rotation([0f32,6.28f32])
+                .angular_velocity([-0.5f32,0.5f32])
+                .size([100f32,100f32]),
+        };
+
+        settings.bounds(bounds)
+    }
+
+    pub fn area(mut self,area:[f32;4])->EmitterSettings{
+        self.area=area;
+        self
+    }
+
+    pub fn bounds(mut self,bounds:[f32;4])->EmitterSettings{
+        self.bounds=Some(bounds);
+        self
+    }
+
+    pub fn rate(mut self,rate:f32)->EmitterSettings{
+        self.rate=rate;
+        self
+    }
+
+    pub fn max_particles(mut self,max_particles:usize)->EmitterSettings{
+        self.max_particles=max_particles;
+        self
+    }
+
+    pub fn velocity(mut self,x:[f32;2],y:[f32;2])->EmitterSettings{
+        self.velocity_x=x;
+        self.velocity_y=y;
+        self
+    }
+
+    pub fn acceleration(mut self,x:[f32;2],y:[f32;2])->EmitterSettings{
+        self.acceleration_x=x;
+        self.acceleration_y=y;
+        self
+    }
+
+    pub fn lifetime(mut self,lifetime:[f32;2])->EmitterSettings{
+        self.lifetime=lifetime;
+        self
+    }
+
+    pub fn rotation(mut self,rotation:[f32;2])->EmitterSettings{
+        self.rotation=rotation;
+        self
+    }
+
+    pub fn angular_velocity(mut self,angular_velocity:[f32;2])->EmitterSettings{
+        self.angular_velocity=angular_velocity;
+        self
+    }
+
+    pub fn size(mut self,size:[f32;2])->EmitterSettings{
+        self.size=size;
+        self
+    }
+
+    pub fn uv(mut self,uv:[f32;4])->EmitterSettings{
+        self.uv=uv;
+        self
+    }
+
+    pub fn colour(mut self,colour:Colour)->EmitterSettings{
+        self.colour=colour;
+        self
+    }
+
+    pub fn seed(mut self,seed:u64)->EmitterSettings{
+        self.seed=seed;
+        self
+    }
+}
+
+#[derive(Clone,Debug)]
+struct Particle{
+    position:[f32;2],
+    velocity:[f32;2],
+    acceleration:[f32;2],
+    rotation:f32,
+    angular_velocity:f32,
+    age:f32,
+    lifetime:f32,
+}
+
+/// Движение частиц без отрисовки.
+///
+/// Одинаковые настройки и зерно дают одинаковые частицы.
+pub struct ParticleSimulation{
+    settings:EmitterSettings,
+    random:Random,
+    particles:Vec<Particle>,
+    /// Накопленная доля следующей частицы
+    spawn:f32,
+}
+
+impl ParticleSimulation{
+    pub fn new(settings:EmitterSettings)->ParticleSimulation{
+        Self{
+            random:Random::new(settings.seed),
+            particles:Vec::with_capacity(settings.max_particles),
+            spawn:0f32,
+            settings,
+        }
+    }
+
+    pub fn len(&self)->usize{
+        self.particles.len()
+    }
+
+    pub fn is_empty(&self)->bool{
+        self.particles.is_empty()
+    }
+
+    /// Удаление всех частиц.
+    pub fn clear(&mut self){
+        self.particles.clear();
+        self.spawn=0f32;
+    }
+
+    /// Положение и поворот частиц.
+    pub fn particles(&self)->impl Iterator<Item=([f32;2],f32)>+'_{
+        self.particles.iter().map(|particle|(particle.position,particle.rotation))
+    }
+
+    fn spawn_particle(&mut self){
+        let settings=&self.settings;
+        let random=&mut self.random;
+
+        let [x,y,width,height]=settings.area;
+
+        let particle=Particle{
+            position:[
+                x+width*random.next_f32(),
+                y+height*random.next_f32(),
+            ],
+            velocity:[
+                random.range(settings.velocity_x),
+                random.range(settings.velocity_y),
+            ],
+            acceleration:[
+                random.range(settings.acceleration_x),
+                random.range(settings.acceleration_y),
+            ],
+            rotation:random.range(settings.rotation),
+            angular_velocity:random.range(settings.angular_velocity),
+            age:0f32,
+            lifetime:random.range(settings.lifetime),
+        };
+
+        self.particles.push(particle);
+    }
+
+    /// Продвижение на `elapsed` секунд.
+    pub fn update(&mut self,elapsed:f32){
+        // Появление новых частиц
+        self.spawn+=self.settings.rate*elapsed;
+        while self.spawn>=1f32{
+            self.spawn-=1f32;
+            if self.particles.len()<self.settings.max_particles{
+                self.spawn_particle();
+            }
+        }
+
+        // Движение
+        for particle in &mut self.particles{
+            particle.velocity[0]+=particle.acceleration[0]*elapsed;
+            particle.velocity[1]+=particle.acceleration[1]*elapsed;
+            particle.position[0]+=particle.velocity[0]*elapsed;
+            particle.position[1]+=particle.velocity[1]*elapsed;
+            particle.rotation+=particle.angular_velocity*elapsed;
+            particle.age+=elapsed;
+        }
+
+        // Удаление отживших и улетевших частиц
+        let bounds=self.settings.bounds;
+        self.particles.retain(|particle|{
+            let inside=match bounds{
+                Some([x,y,width,height])=>{
+                    let [px,py]=particle.position;
+                    px>=x && px<=x+width && py>=y && py<=y+height
+                }
+                None=>true,
+            };
+            inside && particle.age<particle.lifetime
+        });
+    }
+}
+
+/// Источник частиц (дождь, снег, лепестки).
+///
+/// Все частицы рисуются одним текстурным объектом.
+pub struct ParticleEmitter{
+    simulation:ParticleSimulation,
+    object:TexturedObject,
+}
+
+impl ParticleEmitter{
+    pub fn new(settings:EmitterSettings,texture:&TextureObject,graphics:&mut Graphics2D)->ParticleEmitter{
+        // Частица с центром в начале координат
+        let [width,height]=settings.size;
+        let image=ImageObject::raw_uv(
+            [-width/2f32,-height/2f32,width,height],
+            settings.uv,
+            settings.colour
+        );
+
+        Self{
+            object:TexturedObject::new(&image,texture,graphics).unwrap(),
+            simulation:ParticleSimulation::new(settings),
+        }
+    }
+
+    pub fn len(&self)->usize{
+        self.simulation.len()
+    }
+
+    pub fn is_empty(&self)->bool{
+        self.simulation.is_empty()
+    }
+
+    /// Удаление всех частиц.
+    pub fn clear(&mut self){
+        self.simulation.clear()
+    }
+
+    /// Продвижение на `elapsed` секунд.
+    pub fn update(&mut self,elapsed:f32){
+        self.simulation.update(elapsed)
+    }
+
+    pub fn draw<S:Surface>(&self,graphics:&mut Graphics<S>){
+        self.draw_shift([0f32;2],graphics)
+    }
+
+    pub fn draw_shift<S:Surface>(&self,shift:[f32;2],graphics:&mut Graphics<S>){
+        for ([x,y],rotation) in self.simulation.particles(){
+            self.object.draw_shift_rotate([x+shift[0],y+shift[1]],rotation,graphics);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn random_known_sequence(){
+        // Эталонные значения SplitMix64 для зерна 0
+        let mut random=Random::new(0u64);
+        assert_eq!(random.next_u64(),0xE220A8397B1DCDAFu64);
+        assert_eq!(random.next_u64(),0x6E789E6AA1B965F4u64);
+        assert_eq!(random.next_u64(),0x06C45D188009454Fu64);
+
+        let mut random=Random::new(2021u64);
+        for _ in 0..1000{
+            let value=random.range([-2f32,3f32]);
+            assert!(value>=-2f32 && value<=3f32);
+        }
+    }
+
+    fn settings()->EmitterSettings{
+        EmitterSettings::new([0f32,0f32,100f32,100f32])
+            .rate(10f32)
+            .max_particles(1000usize)
+            .velocity([-5f32,5f32],[10f32,20f32])
+            .lifetime([1f32,1f32])
+            .seed(7u64)
+    }
+
+    fn states(simulation:&ParticleSimulation)->Vec<([f32;2],f32)>{
+        simulation.particles().collect()
+    }
+
+    #[test]
+    fn same_seed_same_particles(){
+        let mut a=ParticleSimulation::new(settings());
+        let mut b=ParticleSimulation::new(settings());
+        for _ in 0..50{
+            a.update(0.02f32);
+            b.update(0.02f32);
+        }
+        assert!(a.len()>0usize);
+        assert_eq!(states(&a),states(&b));
+
+        let mut c=ParticleSimulation::new(settings().seed(8u64));
+        for _ in 0..50{
+            c.update(0.02f32);
+        }
+        assert_ne!(states(&a),states(&c));
+    }
+
+    #[test]
+    fn spawn_rate_and_lifetime(){
+        // 16 частиц в секунду, каждая живёт 1 секунду
+        // (шаг 1/32 секунды складывается без ошибок округления)
+        let step=1f32/32f32;
+        let mut simulation=ParticleSimulation::new(settings().rate(16f32));
+        for _ in 0..10{
+            simulation.update(step);
+        }
+        assert_eq!(simulation.len(),5usize);
+
+        // Через 3 секунды живы только появившиеся за последнюю секунду
+        for _ in 0..86{
+            simulation.update(step);
+        }
+        assert!(simulation.len()>=15usize && simulation.len()<=16usize);
+
+        let mut limited=ParticleSimulation::new(settings().max_particles(3usize));
+        for _ in 0..50{
+            limited.update(0.02f32);
+        }
+        assert_eq!(limited.len(),3usize);
+
+        simulation.clear();
+        assert_eq!(simulation.len(),0usize);
+    }
+}
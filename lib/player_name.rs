@@ -0,0 +1,106 @@
+/// Обозначение игрока в диалогах
+pub const player_placeholder:&'static str="{}";
+
+/// Пустое имя говорящего (для мыслей, предыстории и т.п.)
+pub const empty_speaker:&'static str="_";
+
+/// Максимальная длина имени игрока в символах
+pub const max_name_length:usize=20usize;
+
+/// Ошибки проверки имени игрока.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum NameError{
+    /// Пустое имя или имя из одних пробелов
+    Empty,
+    /// Имя длиннее допустимого
+    TooLong,
+    /// Управляющие символы в имени
+    InvalidCharacter,
+}
+
+/// Проверка имени игрока.
+///
+/// Возвращает имя без пробелов по краям.
+pub fn validate_name(name:&str,max_length:usize)->Result<String,NameError>{
+    let name=name.trim();
+
+    if name.is_empty(){
+        Err(NameError::Empty)
+    }
+    else if name.chars().any(char::is_control){
+        Err(NameError::InvalidCharacter)
+    }
+    else if name.chars().count()>max_length{
+        Err(NameError::TooLong)
+    }
+    else{
+        Ok(name.to_string())
+    }
+}
+
+/// Правила подстановки имени игрока в диалоги.
+///
+/// `{}` в качестве говорящего заменяется местоимением (например, 'Я'),
+/// `{}` в тексте реплики - именем игрока,
+/// `_` в качестве говорящего - пустым именем.
+pub struct NameSubstitution{
+    name:String,
+    pronoun:String,
+}
+
+impl NameSubstitution{
+    pub fn new<N:Into<String>,P:Into<String>>(name:N,pronoun:P)->NameSubstitution{
+        Self{
+            name:name.into(),
+            pronoun:pronoun.into(),
+        }
+    }
+
+    /// Имя говорящего для вывода на экран.
+    pub fn speaker<'a>(&'a self,speaker:&'a str)->&'a str{
+        let speaker=speaker.trim();
+        if speaker==player_placeholder{
+            &self.pronoun
+        }
+        else if speaker==empty_speaker{
+            ""
+        }
+        else{
+            speaker
+        }
+    }
+
+    /// Текст реплики с именем игрока.
+    pub fn line(&self,line:&str)->String{
+        line.replace(player_placeholder,&self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn validates_names(){
+        assert_eq!(validate_name("",max_name_length),Err(NameError::Empty));
+        assert_eq!(validate_name("  \t ",max_name_length),Err(NameError::Empty));
+        assert_eq!(validate_name("  Алиса ",max_name_length),Ok("Алиса".to_string()));
+        assert_eq!(validate_name("Ал\u{8}иса",max_name_length),Err(NameError::InvalidCharacter));
+
+        // Длина считается в символах, а не в байтах
+        assert_eq!(validate_name("Ёжик",4usize),Ok("Ёжик".to_string()));
+        assert_eq!(validate_name("Ёжики",4usize),Err(NameError::TooLong));
+    }
+
+    #[test]
+    fn substitutes_player_name(){
+        let substitution=NameSubstitution::new("Алиса","Я");
+
+        assert_eq!(substitution.speaker(" {} "),"Я");
+        assert_eq!(substitution.speaker("_"),"");
+        assert_eq!(substitution.speaker("Кот"),"Кот");
+
+        assert_eq!(substitution.line("Привет, {}! Как дела, {}?"),"Привет, Алиса! Как дела, Алиса?");
+        assert_eq!(substitution.line("Без имени"),"Без имени");
+    }
+}
@@ -0,0 +1,212 @@
+use crate::log;
+
+use std::{
+    env::current_exe,
+    fs::read_dir,
+    path::{Path,PathBuf},
+};
+
+/// Название папки ресурсов по умолчанию
+const default_root:&'static str="resources";
+
+/// Поиск файлов ресурсов.
+///
+/// Папка ресурсов ищется рядом с исполняемым файлом,
+/// а если её там нет - в текущей рабочей папке.
+/// Её можно указать явно аргументом `--resources [путь]`.
+///
+/// Моды подключаются аргументами `--mod [путь]` (можно несколько).
+/// Папка мода повторяет структуру папки ресурсов и может содержать
+/// лишь отдельные файлы - остальные берутся из основной папки.
+/// Последний подключённый мод имеет наивысший приоритет.
+///
+/// Папки `settings` и `screenshots` находятся рядом с папкой ресурсов.
+#[derive(Clone)]
+pub struct ResourceResolver{
+    root:Option<PathBuf>,
+    overlays:Vec<PathBuf>,
+}
+
+impl ResourceResolver{
+    /// Пустой поиск - ресурсы в `./resources`.
+    pub const fn new()->ResourceResolver{
+        Self{
+            root:None,
+            overlays:Vec::new(),
+        }
+    }
+
+    /// Настройка по аргументам командной строки.
+    pub fn from_args<I:Iterator<Item=String>>(mut args:I)->ResourceResolver{
+        let mut resolver=ResourceResolver::new();
+
+        while let Some(arg)=args.next(){
+            match arg.as_str(){
+                "--resources"=>match args.next(){
+                    Some(path)=>resolver.root=Some(PathBuf::from(path)),
+                    None=>log::warning("аргумент --resources без пути пропущен"),
+                }
+
+                "--mod"=>match args.next(){
+                    Some(path)=>resolver.add_overlay(path),
+                    None=>log::warning("аргумент --mod без пути пропущен"),
+                }
+
+                _=>{}
+            }
+        }
+
+        if resolver.root.is_none(){
+            resolver.root=executable_root();
+        }
+
+        resolver
+    }
+
+    /// Подключение папки мода.
+    pub fn add_overlay<P:Into<PathBuf>>(&mut self,path:P){
+        self.overlays.push(path.into())
+    }
+
+    /// Папка ресурсов.
+    pub fn root(&self)->&Path{
+        match &self.root{
+            Some(root)=>root.as_path(),
+            None=>Path::new(default_root),
+        }
+    }
+
+    /// Папки модов в порядке подключения.
+    pub fn overlays(&self)->&[PathBuf]{
+        &self.overlays
+    }
+
+    /// Путь к ресурсу относительно папки ресурсов
+    /// с учётом подключённых модов.
+    pub fn resolve<P:AsRef<Path>>(&self,relative:P)->PathBuf{
+        let relative=relative.as_ref();
+
+        for overlay in self.overlays.iter().rev(){
+            let path=overlay.join(relative);
+            if path.exists(){
+                return path
+            }
+        }
+
+        self.root().join(relative)
+    }
+
+    /// Файлы папки ресурсов с учётом подключённых модов.
+    ///
+    /// Файл мода заменяет одноимённый файл основной папки.
+    pub fn files<P:AsRef<Path>>(&self,relative:P)->Vec<PathBuf>{
+        let relative=relative.as_ref();
+
+        let mut files:Vec<PathBuf>=Vec::new();
+
+        let directories=self.overlays.iter().rev()
+                .map(|overlay|overlay.join(relative))
+                .chain(Some(self.root().join(relative)));
+
+        for directory in directories{
+            let entries=match read_dir(directory){
+                Ok(entries)=>entries,
+                Err(_)=>continue,
+            };
+
+            for entry in entries.filter_map(|entry|entry.ok()){
+                let path=entry.path();
+                if !path.is_file(){
+                    continue
+                }
+
+                if !files.iter().any(|file|file.file_name()==path.file_name()){
+                    files.push(path)
+                }
+            }
+        }
+
+        files
+    }
+
+    /// Папка для пользовательских файлов (настроек и скриншотов).
+    pub fn user_directory(&self)->&Path{
+        match self.root().parent(){
+            Some(parent)=>parent,
+            None=>Path::new("."),
+        }
+    }
+
+    /// Путь к пользовательскому файлу.
+    pub fn user_path<P:AsRef<Path>>(&self,relative:P)->PathBuf{
+        self.user_directory().join(relative)
+    }
+}
+
+/// Папка ресурсов рядом с исполняемым файлом, если она есть.
+fn executable_root()->Option<PathBuf>{
+    let executable=current_exe().ok()?;
+    let root=executable.parent()?.join(default_root);
+
+    if root.is_dir(){
+        Some(root)
+    }
+    else{
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    use std::fs::{create_dir_all,remove_dir_all,write};
+
+    fn args(line:&str)->impl Iterator<Item=String>+'_{
+        line.split_whitespace().map(|arg|arg.to_string())
+    }
+
+    #[test]
+    fn parses_arguments(){
+        let resolver=ResourceResolver::from_args(args("--resources data --unknown --mod a --mod b"));
+        assert_eq!(resolver.root(),Path::new("data"));
+        assert_eq!(resolver.overlays(),&[PathBuf::from("a"),PathBuf::from("b")]);
+        assert_eq!(resolver.user_path("settings"),Path::new("settings"));
+
+        // Аргумент без значения пропускается
+        let resolver=ResourceResolver::from_args(args("--resources data --mod"));
+        assert!(resolver.overlays().is_empty());
+    }
+
+    #[test]
+    fn later_overlays_take_precedence(){
+        let directory=std::env::temp_dir().join(format!("resolver_{}",std::process::id()));
+        let root=directory.join("resources");
+        let first=directory.join("first");
+        let second=directory.join("second");
+        for folder in [&root,&first,&second].iter(){
+            create_dir_all(folder.join("images")).unwrap();
+        }
+        write(root.join("images/a.png"),"").unwrap();
+        write(root.join("images/b.png"),"").unwrap();
+        write(first.join("images/a.png"),"").unwrap();
+        write(first.join("images/b.png"),"").unwrap();
+        write(second.join("images/b.png"),"").unwrap();
+
+        let mut resolver=ResourceResolver::new();
+        resolver.root=Some(root.clone());
+        resolver.add_overlay(&first);
+        resolver.add_overlay(&second);
+
+        assert_eq!(resolver.resolve("images/a.png"),first.join("images/a.png"));
+        assert_eq!(resolver.resolve("images/b.png"),second.join("images/b.png"));
+        // Файла нет ни в одном моде - путь в основной папке
+        assert_eq!(resolver.resolve("images/c.png"),root.join("images/c.png"));
+
+        let mut files=resolver.files("images");
+        files.sort();
+        assert_eq!(files,vec![first.join("images/a.png"),second.join("images/b.png")]);
+
+        let _=remove_dir_all(&directory);
+    }
+}
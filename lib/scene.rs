@@ -0,0 +1,304 @@
+use crate::{
+    log,
+    objects,
+    transition::{Transition,TransitionObjects},
+};
+
+use cat_engine::{
+    // functions
+    default_draw_parameters,
+    // structs
+    Window,
+    WindowEvent,
+    graphics::{Graphics,Graphics2D},
+    texture::Texture,
+    glium::{
+        Frame,
+        framebuffer::SimpleFrameBuffer,
+    },
+};
+
+/// Построение сцены.
+///
+/// Сцена строится только после удаления предыдущей,
+/// чтобы графические объекты добавлялись и удалялись по порядку.
+pub type SceneBuilder<C>=Box<dyn FnOnce(&mut C,&mut Window,&mut Graphics2D)->Box<dyn Scene<C>>>;
+
+/// Переход между сценами.
+pub enum SceneAction<C>{
+    /// Остаться на текущей сцене
+    None,
+    /// Открыть сцену поверх текущей
+    Push(SceneBuilder<C>),
+    /// Вернуться к предыдущей сцене
+    Pop,
+    /// Заменить текущую сцену
+    Replace(SceneBuilder<C>),
+    /// Закрыть все сцены (выход из игры)
+    Exit,
+    /// Выполнить действие и показать переход
+    /// от отрисовки закрытой сцены к верхней
+    Transition(Transition,Box<SceneAction<C>>),
+}
+
+impl<C> SceneAction<C>{
+    pub fn push<F>(builder:F)->SceneAction<C>
+        where F:FnOnce(&mut C,&mut Window,&mut Graphics2D)->Box<dyn Scene<C>>+'static
+    {
+        SceneAction::Push(Box::new(builder))
+    }
+
+    pub fn replace<F>(builder:F)->SceneAction<C>
+        where F:FnOnce(&mut C,&mut Window,&mut Graphics2D)->Box<dyn Scene<C>>+'static
+    {
+        SceneAction::Replace(Box::new(builder))
+    }
+
+    /// Действие с переходом.
+    pub fn with_transition(self,transition:Transition)->SceneAction<C>{
+        SceneAction::Transition(transition,Box::new(self))
+    }
+
+    /// Меняется ли верхняя сцена.
+    fn changes_scene(&self)->bool{
+        match self{
+            SceneAction::None | SceneAction::Exit=>false,
+            SceneAction::Transition(_,action)=>action.changes_scene(),
+            _=>true,
+        }
+    }
+}
+
+/// Страница игры.
+///
+/// `C` - общие данные всех сцен.
+pub trait Scene<C>{
+    /// Имя сцены для журнала (по умолчанию - имя типа без пути).
+    fn name(&self)->&'static str{
+        let name=std::any::type_name::<Self>();
+        name.rsplit("::").next().unwrap_or(name)
+    }
+
+    /// Сцена добавлена в стек.
+    fn enter(&mut self,_context:&mut C,_window:&mut Window,_graphics:&mut Graphics2D){}
+
+    /// Сцена снова стала верхней после закрытия сцены над ней.
+    fn resume(&mut self,_context:&mut C,_window:&mut Window,_graphics:&mut Graphics2D)->SceneAction<C>{
+        SceneAction::None
+    }
+
+    /// Сцена удалена из стека.
+    ///
+    /// Графические объекты сцены удаляются вместе с ней.
+    fn exit(&mut self,_context:&mut C,_window:&mut Window,_graphics:&mut Graphics2D){}
+
+    /// Обновление состояния.
+    ///
+    /// `elapsed` - время с предыдущего обновления в секундах.
+    fn update(&mut self,_elapsed:f32,_context:&mut C,_window:&mut Window,_graphics:&mut Graphics2D)->SceneAction<C>{
+        SceneAction::None
+    }
+
+    /// Размер окна изменён - перестроение разметки.
+    fn resize(&mut self,_context:&mut C,_window:&mut Window,_graphics:&mut Graphics2D)->SceneAction<C>{
+        SceneAction::None
+    }
+
+    /// Обработка событий ввода.
+    fn input(&mut self,_event:WindowEvent,_context:&mut C,_window:&mut Window,_graphics:&mut Graphics2D)->SceneAction<C>{
+        SceneAction::None
+    }
+
+    /// Отрисовка на экран.
+    ///
+    /// `shift` - сдвиг страницы во время перехода.
+    fn draw(&self,shift:[f32;2],context:&C,graphics:&mut Graphics<Frame>);
+
+    /// Отрисовка на текстуру для перехода между сценами.
+    fn draw_on_texture(&self,context:&C,graphics:&mut Graphics<SimpleFrameBuffer>);
+}
+
+/// Стек сцен.
+///
+/// События передаются только верхней сцене.
+/// Перед каждой сменой верхней сцены она отрисовывается
+/// на текстуру объекта для переходов.
+///
+/// Во время перехода ввод сценам не передаётся.
+pub struct SceneStack<C>{
+    scenes:Vec<Box<dyn Scene<C>>>,
+    /// Текущий переход к верхней сцене
+    transition:Option<Transition>,
+    /// Графические объекты для переходов
+    transition_objects:fn(&C)->&TransitionObjects,
+}
+
+impl<C> SceneStack<C>{
+    pub fn new(transition_objects:fn(&C)->&TransitionObjects)->SceneStack<C>{
+        Self{
+            scenes:Vec::new(),
+            transition:None,
+            transition_objects,
+        }
+    }
+
+    pub fn is_empty(&self)->bool{
+        self.scenes.is_empty()
+    }
+
+    pub fn len(&self)->usize{
+        self.scenes.len()
+    }
+
+    pub fn push(&mut self,builder:SceneBuilder<C>,context:&mut C,window:&mut Window,graphics:&mut Graphics2D){
+        self.handle(SceneAction::Push(builder),context,window,graphics)
+    }
+
+    pub fn pop(&mut self,context:&mut C,window:&mut Window,graphics:&mut Graphics2D){
+        self.handle(SceneAction::Pop,context,window,graphics)
+    }
+
+    pub fn replace(&mut self,builder:SceneBuilder<C>,context:&mut C,window:&mut Window,graphics:&mut Graphics2D){
+        self.handle(SceneAction::Replace(builder),context,window,graphics)
+    }
+
+    /// Удаление всех сцен.
+    pub fn clear(&mut self,context:&mut C,window:&mut Window,graphics:&mut Graphics2D){
+        while !self.scenes.is_empty(){
+            self.remove_top(context,window,graphics);
+        }
+    }
+
+    /// Выполнение перехода.
+    pub fn handle(&mut self,action:SceneAction<C>,context:&mut C,window:&mut Window,graphics:&mut Graphics2D){
+        self.apply(action,true,context,window,graphics)
+    }
+
+    pub fn update(&mut self,elapsed:f32,context:&mut C,window:&mut Window,graphics:&mut Graphics2D){
+        if let Some(transition)=&mut self.transition{
+            if !transition.update(elapsed,(self.transition_objects)(context),graphics){
+                self.transition=None;
+            }
+        }
+
+        if let Some(scene)=self.scenes.last_mut(){
+            let action=scene.update(elapsed,context,window,graphics);
+            self.handle(action,context,window,graphics);
+        }
+    }
+
+    pub fn input(&mut self,event:WindowEvent,context:&mut C,window:&mut Window,graphics:&mut Graphics2D){
+        // Во время перехода ввод не обрабатывается
+        if self.transition.is_some(){
+            return
+        }
+
+        if let Some(scene)=self.scenes.last_mut(){
+            let action=scene.input(event,context,window,graphics);
+            self.handle(action,context,window,graphics);
+        }
+    }
+
+    /// Изменение размера окна.
+    ///
+    /// Перестраивается только верхняя сцена, остальные -
+    /// при возвращении к ним.
+    pub fn resize(&mut self,context:&mut C,window:&mut Window,graphics:&mut Graphics2D){
+        if let Some(scene)=self.scenes.last_mut(){
+            let action=scene.resize(context,window,graphics);
+            // Старая разметка для переходов не нужна
+            self.apply(action,false,context,window,graphics);
+        }
+    }
+
+    pub fn draw(&self,context:&C,graphics:&mut Graphics<Frame>){
+        if let Some(scene)=self.scenes.last(){
+            if let Some(transition)=&self.transition{
+                transition.draw((self.transition_objects)(context),graphics,|shift,graphics|{
+                    scene.draw(shift,context,graphics)
+                });
+            }
+            else{
+                scene.draw([0f32;2],context,graphics)
+            }
+        }
+    }
+
+    fn apply(&mut self,action:SceneAction<C>,snapshot:bool,context:&mut C,window:&mut Window,graphics:&mut Graphics2D){
+        if action.changes_scene(){
+            if snapshot{
+                self.snapshot(context,window,graphics);
+            }
+            // Переход к сцене, которая будет закрыта, больше не нужен
+            self.transition=None;
+        }
+
+        match action{
+            SceneAction::None=>{}
+            SceneAction::Transition(transition,action)=>{
+                self.apply(*action,false,context,window,graphics);
+                self.transition=Some(transition);
+            }
+            SceneAction::Push(builder)=>self.push_scene(builder,context,window,graphics),
+            SceneAction::Pop=>{
+                self.remove_top(context,window,graphics);
+                if let Some(scene)=self.scenes.last_mut(){
+                    // Переход, начатый при возвращении, использует уже готовую
+                    // отрисовку закрытой сцены
+                    let action=scene.resume(context,window,graphics);
+                    self.apply(action,false,context,window,graphics);
+                }
+            }
+            SceneAction::Replace(builder)=>{
+                self.remove_top(context,window,graphics);
+                self.push_scene(builder,context,window,graphics);
+            }
+            SceneAction::Exit=>self.clear(context,window,graphics),
+        }
+    }
+
+    fn push_scene(&mut self,builder:SceneBuilder<C>,context:&mut C,window:&mut Window,graphics:&mut Graphics2D){
+        let mut scene=builder(context,window,graphics);
+        log::info(format!("сцена '{}' открыта",scene.name()));
+        log::set_scene(scene.name());
+        scene.enter(context,window,graphics);
+        self.scenes.push(scene);
+    }
+
+    fn remove_top(&mut self,context:&mut C,window:&mut Window,graphics:&mut Graphics2D){
+        if let Some(mut scene)=self.scenes.pop(){
+            log::info(format!("сцена '{}' закрыта",scene.name()));
+            scene.exit(context,window,graphics);
+            drop(scene);
+            objects::collect(graphics);
+        }
+        log::set_scene(self.scenes.last().map_or("",|scene|scene.name()));
+    }
+
+    /// Отрисовка верхней сцены на текстуру для переходов.
+    fn snapshot(&self,context:&C,window:&Window,graphics:&mut Graphics2D){
+        if let Some(scene)=self.scenes.last(){
+            (self.transition_objects)(context).capture(window,graphics,|graphics|{
+                scene.draw_on_texture(context,graphics)
+            });
+        }
+    }
+}
+
+/// Отрисовка на текстуру.
+pub fn draw_on_texture<F:FnOnce(&mut Graphics<SimpleFrameBuffer>)>(
+    texture:&Texture,
+    window:&Window,
+    graphics:&Graphics2D,
+    f:F
+){
+    let mut frame_buffer=SimpleFrameBuffer::new(window.display(),&texture.0).unwrap();
+
+    let mut frame_buffer_graphics=Graphics{
+        graphics2d:graphics,
+        draw_parameters:default_draw_parameters(),
+        frame:&mut frame_buffer,
+    };
+
+    f(&mut frame_buffer_graphics);
+}
@@ -0,0 +1,162 @@
+use crate::{
+    error::{GameError,GameResult},
+    log,
+};
+
+use cat_engine::image::{self,RgbaImage};
+
+use std::{
+    fs::{create_dir_all,read_dir},
+    path::{Path,PathBuf},
+    sync::mpsc::{channel,Sender,Receiver},
+    thread::{spawn,JoinHandle},
+    time::{SystemTime,UNIX_EPOCH},
+};
+
+/// Имя файла снимка по времени (UTC):
+/// `screenshot_[год]-[месяц]-[день]_[часы]-[минуты]-[секунды]`.
+pub fn screenshot_name(secs:u64)->String{
+    let (year,month,day)=civil_date((secs/86400u64) as i64);
+    let time=secs%86400u64;
+    format!(
+        "screenshot_{:04}-{:02}-{:02}_{:02}-{:02}-{:02}",
+        year,month,day,
+        time/3600u64,
+        time%3600u64/60u64,
+        time%60u64
+    )
+}
+
+/// Дата по номеру дня от 1970-01-01 (григорианский календарь).
+fn civil_date(days:i64)->(i64,u32,u32){
+    let days=days+719468i64;
+    let era=(if days>=0{days}else{days-146096i64})/146097i64;
+    // День и год в 400-летнем цикле
+    let day_of_era=days-era*146097i64;
+    let year_of_era=(day_of_era-day_of_era/1460i64+day_of_era/36524i64-day_of_era/146096i64)/365i64;
+    let day_of_year=day_of_era-(365i64*year_of_era+year_of_era/4i64-year_of_era/100i64);
+    // Месяц, начиная с марта
+    let month=(5i64*day_of_year+2i64)/153i64;
+    let day=day_of_year-(153i64*month+2i64)/5i64+1i64;
+    let month=if month<10i64{month+3i64}else{month-9i64};
+    let year=year_of_era+era*400i64+if month<=2i64{1i64}else{0i64};
+    (year,month as u32,day as u32)
+}
+
+/// Свободный путь `[папка]/[имя].png` (или `[имя]_2.png`, `[имя]_3.png`, ...).
+fn unique_path(directory:&Path,name:&str)->PathBuf{
+    let mut path=directory.join(format!("{}.png",name));
+    let mut n=2u32;
+    while path.exists(){
+        path=directory.join(format!("{}_{}.png",name,n));
+        n+=1;
+    }
+    path
+}
+
+/// Снимки экрана в папке `directory`, новые первыми.
+pub fn screenshots<P:AsRef<Path>>(directory:P)->Vec<PathBuf>{
+    let entries=match read_dir(directory){
+        Ok(entries)=>entries,
+        Err(_)=>return Vec::new(),
+    };
+
+    let mut files:Vec<(SystemTime,PathBuf)>=entries.flatten()
+            .map(|entry|entry.path())
+            .filter(|path|path.extension().map_or(false,|extension|extension=="png"))
+            .map(|path|{
+                let modified=path.metadata().and_then(|metadata|metadata.modified()).unwrap_or(UNIX_EPOCH);
+                (modified,path)
+            })
+            .collect();
+
+    files.sort_by(|a,b|b.cmp(a));
+    files.into_iter().map(|(_,path)|path).collect()
+}
+
+/// Загрузка снимка, уменьшенного с сохранением пропорций
+/// до размера не больше `size`.
+pub fn load_thumbnail<P:AsRef<Path>>(path:P,[width,height]:[u32;2])->GameResult<RgbaImage>{
+    let path=path.as_ref();
+    let image=image::open(path).map_err(|e|GameError::file(path,e))?;
+    Ok(image.thumbnail(width.max(1u32),height.max(1u32)).into_rgba8())
+}
+
+/// Сохранение снимков экрана в формате PNG в отдельном потоке.
+///
+/// Папка создаётся при первом сохранении. Файлы называются по времени
+/// снимка и не перезаписывают существующие. Результаты сохранения
+/// забираются через `poll`.
+pub struct ScreenshotSaver{
+    jobs:Option<Sender<(u64,RgbaImage)>>,
+    results:Receiver<GameResult<PathBuf>>,
+    thread:Option<JoinHandle<()>>,
+}
+
+impl ScreenshotSaver{
+    pub fn new<P:Into<PathBuf>>(directory:P)->ScreenshotSaver{
+        let directory=directory.into();
+        let (jobs,job_receiver)=channel::<(u64,RgbaImage)>();
+        let (result_sender,results)=channel();
+
+        let thread=spawn(move||{
+            // Очередь закрывается при удалении - поток дописывает снимки и завершается
+            for (secs,image) in job_receiver{
+                let result=create_dir_all(&directory)
+                        .map_err(|e|GameError::file(&directory,e))
+                        .and_then(|_|{
+                            let path=unique_path(&directory,&screenshot_name(secs));
+                            image.save(&path).map_err(|e|GameError::file(&path,e))?;
+                            Ok(path)
+                        });
+
+                if result_sender.send(result).is_err(){
+                    return
+                }
+            }
+        });
+
+        Self{
+            jobs:Some(jobs),
+            results,
+            thread:Some(thread),
+        }
+    }
+
+    /// Постановка снимка в очередь сохранения.
+    pub fn save(&self,image:RgbaImage){
+        let secs=SystemTime::now().duration_since(UNIX_EPOCH).map_or(0u64,|time|time.as_secs());
+        if let Some(jobs)=&self.jobs{
+            if jobs.send((secs,image)).is_err(){
+                log::error("поток сохранения снимков остановлен");
+            }
+        }
+    }
+
+    /// Результат очередного сохранения (путь к файлу или ошибка).
+    pub fn poll(&self)->Option<GameResult<PathBuf>>{
+        self.results.try_recv().ok()
+    }
+}
+
+impl Drop for ScreenshotSaver{
+    fn drop(&mut self){
+        self.jobs=None;
+        if let Some(thread)=self.thread.take(){
+            let _=thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn names_screenshots_by_utc_time(){
+        assert_eq!(screenshot_name(0u64),"screenshot_1970-01-01_00-00-00");
+        assert_eq!(screenshot_name(1_700_000_000u64),"screenshot_2023-11-14_22-13-20");
+        // 29 февраля високосного года
+        assert_eq!(screenshot_name(951_782_400u64),"screenshot_2000-02-29_00-00-00");
+    }
+}
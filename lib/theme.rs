@@ -0,0 +1,472 @@
+use crate::{
+    colours::*,
+    cursor::CursorState,
+    error::{GameError,GameResult},
+    log,
+    localization::StringTable,
+    manifest::ResourceManifest,
+    objects::TextureObject,
+    user_interface::{BackgroundStyle,NineSlice,rounded_rect_image},
+};
+
+use cat_engine::{
+    Colour,
+    graphics::Graphics2D,
+    texture::Texture,
+    image::GenericImageView,
+    glium::Display,
+};
+
+use std::{
+    fs::read_to_string,
+    io::{Error,ErrorKind},
+    path::Path,
+    rc::Rc,
+};
+
+/// Цвета виджета в разных состояниях.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct StateColours{
+    pub normal:Colour,
+    /// Под курсором
+    pub hover:Colour,
+    pub pressed:Colour,
+}
+
+/// Описание заднего фона виджета.
+#[derive(Clone,Debug)]
+pub struct BackgroundTheme{
+    /// Изображение из раздела `images` манифеста
+    pub image:Option<String>,
+    /// Неизменяемые края изображения [left,top,right,bottom] в пикселях
+    pub slice:[f32;4],
+    pub border_width:f32,
+    pub border_colour:Colour,
+    /// Радиус скругления углов (если нет изображения)
+    pub corner_radius:f32,
+}
+
+impl BackgroundTheme{
+    pub const fn new()->BackgroundTheme{
+        Self{
+            image:None,
+            slice:[0f32;4],
+            border_width:0f32,
+            border_colour:Black,
+            corner_radius:0f32,
+        }
+    }
+
+    /// Построение вида фона: загрузка изображения,
+    /// создание скруглённой рамки или простой прямоугольник.
+    pub fn style(&self,manifest:&ResourceManifest,display:&Display,graphics:&mut Graphics2D)->GameResult<BackgroundStyle>{
+        if let Some(name)=&self.image{
+            let path=manifest.require_image(name)?;
+
+            let image=cat_engine::image::open(path).map_err(|e|GameError::file(path,e))?;
+            let (width,height)=image.dimensions();
+            let texture=Texture::from_image(&image.into_rgba8(),display).map_err(GameError::system)?;
+
+            return Ok(BackgroundStyle::Image{
+                texture:Rc::new(TextureObject::new(texture,graphics)),
+                slice:NineSlice::new([width as f32,height as f32],self.slice),
+            })
+        }
+
+        if self.corner_radius>0f32{
+            let (image,slice)=rounded_rect_image(
+                self.corner_radius.round() as u32,
+                self.border_width.round() as u32,
+                self.border_colour
+            );
+            let texture=Texture::from_image(&image,display).map_err(GameError::system)?;
+
+            return Ok(BackgroundStyle::Image{
+                texture:Rc::new(TextureObject::new(texture,graphics)),
+                slice,
+            })
+        }
+
+        Ok(BackgroundStyle::Flat{
+            border_width:self.border_width,
+            border_colour:self.border_colour,
+        })
+    }
+}
+
+/// Оформление (тема) интерфейса.
+///
+/// Формат файла совпадает с форматом таблицы строк:
+/// ```text
+/// // Палитра - именованные цвета
+/// palette.[имя] = #RRGGBB или #RRGGBBAA
+///
+/// [параметр] = [значение]
+/// ```
+/// Цвет задаётся именем из палитры, шестнадцатеричной записью
+/// или четырьмя числами от 0 до 1. Размер - двумя числами через пробел.
+/// Не указанные параметры остаются по умолчанию.
+///
+/// Фон кнопок (`button.`), полей ввода (`edit.`) и окна диалога (`dialogue.`) задаётся параметрами
+/// `image`, `slice`, `border_width`, `border_colour` и `corner_radius`.
+/// Изображения загружаются после создания окна (`load_styles`).
+///
+/// Курсор задаётся параметрами `cursor.size` и `cursor.[состояние].hotspot`.
+#[derive(Clone)]
+pub struct Theme{
+    palette:Vec<(String,Colour)>,
+
+    /// Номер шрифта интерфейса
+    pub font:usize,
+    pub font_size:f32,
+    pub text_colour:Colour,
+    /// Цвет надписей поверх обоев
+    pub label_colour:Colour,
+
+    /// Фон страниц без обоев
+    pub page_colour:Colour,
+    /// Фон экрана загрузки
+    pub loading_colour:Colour,
+
+    pub header_size:[f32;2],
+    pub header_font_size:f32,
+    pub header_colour:Colour,
+    /// Расстояние между заголовком и кнопками меню
+    pub header_spacing:f32,
+
+    pub button_size:[f32;2],
+    pub button_font_size:f32,
+    pub button_text_colour:Colour,
+    pub button_colours:StateColours,
+    /// Расстояние между кнопками
+    pub button_spacing:f32,
+    pub button_background:BackgroundTheme,
+    pub button_style:BackgroundStyle,
+
+    pub edit_font_size:f32,
+    pub edit_text_colour:Colour,
+    pub edit_background_colour:Colour,
+    pub edit_background:BackgroundTheme,
+    pub edit_style:BackgroundStyle,
+
+    /// Цвет, на который умножается фон окна диалога
+    pub dialogue_colour:Colour,
+    pub dialogue_background:BackgroundTheme,
+    pub dialogue_style:BackgroundStyle,
+
+    pub progress_track_colour:Colour,
+    pub progress_fill_colour:Colour,
+    /// Цвет сообщений об ошибках
+    pub error_colour:Colour,
+
+    /// Отступ элементов от края страницы
+    pub page_padding:f32,
+
+    /// Размер курсора в виртуальных пикселях
+    pub cursor_size:f32,
+    /// Горячие точки изображений курсора по состояниям
+    /// в пикселях изображения (`None` - центр)
+    pub cursor_hotspots:[Option<[f32;2]>;4],
+}
+
+impl Theme{
+    /// Оформление по умолчанию.
+    pub const fn new()->Theme{
+        Self{
+            palette:Vec::new(),
+
+            font:0usize,
+            font_size:20f32,
+            text_colour:Black,
+            label_colour:White,
+
+            page_colour:Dark_gray,
+            loading_colour:White,
+
+            header_size:[100f32,60f32],
+            header_font_size:40f32,
+            header_colour:Head_main_menu_colour,
+            header_spacing:50f32,
+
+            button_size:[100f32,60f32],
+            button_font_size:20f32,
+            button_text_colour:Black,
+            button_colours:StateColours{
+                normal:Light_blue,
+                hover:Light_blue_1,
+                pressed:[
+                    Light_blue[0]-0.05,
+                    Light_blue[1]-0.05,
+                    Light_blue[2]-0.05,
+                    Light_blue[3],
+                ],
+            },
+            button_spacing:10f32,
+            button_background:BackgroundTheme::new(),
+            button_style:BackgroundStyle::flat(),
+
+            edit_font_size:20f32,
+            edit_text_colour:Black,
+            edit_background_colour:White,
+            edit_background:BackgroundTheme::new(),
+            edit_style:BackgroundStyle::flat(),
+
+            dialogue_colour:White,
+            dialogue_background:BackgroundTheme::new(),
+            dialogue_style:BackgroundStyle::flat(),
+
+            progress_track_colour:[0.85,0.85,0.85,1.0],
+            progress_fill_colour:Light_blue,
+            error_colour:Red,
+
+            page_padding:10f32,
+
+            cursor_size:30f32,
+            cursor_hotspots:[None;4],
+        }
+    }
+
+    pub fn load<P:AsRef<Path>>(path:P)->GameResult<Theme>{
+        let path=path.as_ref();
+        log::info(format!("оформление {}",path.display()));
+        let text=read_to_string(path).map_err(|e|GameError::file(path,e))?;
+        Self::parse(&text).map_err(|e|GameError::file(path,e))
+    }
+
+    pub fn parse(text:&str)->Result<Theme,Error>{
+        let table=StringTable::parse(text)?;
+
+        let mut parsed=Theme::new();
+
+        // Сначала палитра, так как на неё ссылаются остальные параметры.
+        // Цвета палитры могут ссылаться друг на друга в любом порядке,
+        // поэтому разбираются проходами, пока находятся новые
+        let mut pending:Vec<(&str,&str)>=table.entries()
+                .filter(|(key,_)|key.starts_with("palette."))
+                .collect();
+        while !pending.is_empty(){
+            let count=pending.len();
+            let palette=&mut parsed.palette;
+            pending.retain(|&(key,value)|{
+                match parse_colour(value,palette){
+                    Some(colour)=>{
+                        palette.push((key["palette.".len()..].to_string(),colour));
+                        false
+                    }
+                    None=>true,
+                }
+            });
+
+            // Неизвестное имя или ссылки по кругу
+            if pending.len()==count{
+                let (key,value)=pending[0];
+                return Err(theme_error(key,value))
+            }
+        }
+
+        for (key,value) in table.entries(){
+            if key.starts_with("palette."){
+                continue
+            }
+
+            let palette=&parsed.palette;
+            let colour=||parse_colour(value,palette).ok_or_else(||theme_error(key,value));
+            let number=||value.parse::<f32>().map_err(|_|theme_error(key,value));
+            let size=||parse_size(value).ok_or_else(||theme_error(key,value));
+
+            // Параметры фона виджетов
+            if let Some((widget,field))=key.split_once('.'){
+                let background=match widget{
+                    "button"=>Some(&mut parsed.button_background),
+                    "edit"=>Some(&mut parsed.edit_background),
+                    "dialogue"=>Some(&mut parsed.dialogue_background),
+                    _=>None,
+                };
+
+                if let Some(background)=background{
+                    let known=match field{
+                        "image"=>{
+                            background.image=Some(value.to_string());
+                            true
+                        }
+                        "slice"=>{
+                            background.slice=parse_insets(value).ok_or_else(||theme_error(key,value))?;
+                            true
+                        }
+                        "border_width"=>{
+                            background.border_width=number()?;
+                            true
+                        }
+                        "border_colour"=>{
+                            background.border_colour=colour()?;
+                            true
+                        }
+                        "corner_radius"=>{
+                            background.corner_radius=number()?;
+                            true
+                        }
+                        _=>false,
+                    };
+
+                    if known{
+                        continue
+                    }
+                }
+            }
+
+            match key{
+                "font"=>parsed.font=value.parse::<usize>().map_err(|_|theme_error(key,value))?,
+                "font_size"=>parsed.font_size=number()?,
+                "text_colour"=>parsed.text_colour=colour()?,
+                "label_colour"=>parsed.label_colour=colour()?,
+
+                "page_colour"=>parsed.page_colour=colour()?,
+                "loading_colour"=>parsed.loading_colour=colour()?,
+
+                "header.size"=>parsed.header_size=size()?,
+                "header.font_size"=>parsed.header_font_size=number()?,
+                "header.colour"=>parsed.header_colour=colour()?,
+                "header.spacing"=>parsed.header_spacing=number()?,
+
+                "button.size"=>parsed.button_size=size()?,
+                "button.font_size"=>parsed.button_font_size=number()?,
+                "button.text_colour"=>parsed.button_text_colour=colour()?,
+                "button.normal"=>parsed.button_colours.normal=colour()?,
+                "button.hover"=>parsed.button_colours.hover=colour()?,
+                "button.pressed"=>parsed.button_colours.pressed=colour()?,
+                "button.spacing"=>parsed.button_spacing=number()?,
+
+                "edit.font_size"=>parsed.edit_font_size=number()?,
+                "edit.text_colour"=>parsed.edit_text_colour=colour()?,
+                "edit.background"=>parsed.edit_background_colour=colour()?,
+
+                "dialogue.colour"=>parsed.dialogue_colour=colour()?,
+
+                "progress.track"=>parsed.progress_track_colour=colour()?,
+                "progress.fill"=>parsed.progress_fill_colour=colour()?,
+                "error_colour"=>parsed.error_colour=colour()?,
+
+                "page_padding"=>parsed.page_padding=number()?,
+
+                "cursor.size"=>parsed.cursor_size=number()?,
+                "cursor.default.hotspot"=>parsed.cursor_hotspots[CursorState::Default as usize]=Some(size()?),
+                "cursor.hover.hotspot"=>parsed.cursor_hotspots[CursorState::Hover as usize]=Some(size()?),
+                "cursor.text.hotspot"=>parsed.cursor_hotspots[CursorState::TextInput as usize]=Some(size()?),
+                "cursor.busy.hotspot"=>parsed.cursor_hotspots[CursorState::Busy as usize]=Some(size()?),
+
+                _=>return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("theme: неизвестный параметр '{}'",key)
+                ))
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    /// Создание видов фона виджетов.
+    ///
+    /// Вызывается после создания окна, до создания страниц.
+    pub fn load_styles(&mut self,manifest:&ResourceManifest,display:&Display,graphics:&mut Graphics2D)->GameResult<()>{
+        self.button_style=self.button_background.style(manifest,display,graphics)?;
+        self.edit_style=self.edit_background.style(manifest,display,graphics)?;
+        self.dialogue_style=self.dialogue_background.style(manifest,display,graphics)?;
+        Ok(())
+    }
+
+    /// Цвет из палитры по имени.
+    pub fn colour(&self,name:&str)->Option<Colour>{
+        self.palette.iter().find(|entry|entry.0==name).map(|entry|entry.1)
+    }
+}
+
+/// Разбор цвета: имя из палитры, `#RRGGBB`, `#RRGGBBAA` или `r g b a`.
+fn parse_colour(value:&str,palette:&[(String,Colour)])->Option<Colour>{
+    if let Some(hex)=value.strip_prefix('#'){
+        if !(hex.len()==6 || hex.len()==8) || !hex.is_ascii(){
+            return None
+        }
+
+        let mut colour=[1f32;4];
+        for (c,channel) in colour.iter_mut().enumerate().take(hex.len()/2){
+            let byte=u8::from_str_radix(&hex[c*2..c*2+2],16).ok()?;
+            *channel=byte as f32/255f32;
+        }
+        return Some(colour)
+    }
+
+    if let Some(entry)=palette.iter().find(|entry|entry.0==value){
+        return Some(entry.1)
+    }
+
+    let mut colour=[0f32;4];
+    let mut parts=value.split_whitespace();
+    for channel in colour.iter_mut(){
+        *channel=parts.next()?.parse().ok()?;
+    }
+
+    if parts.next().is_some(){
+        None
+    }
+    else{
+        Some(colour)
+    }
+}
+
+/// Разбор размера `width height`.
+fn parse_size(value:&str)->Option<[f32;2]>{
+    let mut parts=value.split_whitespace();
+    let size=[parts.next()?.parse().ok()?,parts.next()?.parse().ok()?];
+
+    if parts.next().is_some(){
+        None
+    }
+    else{
+        Some(size)
+    }
+}
+
+/// Разбор краёв `left top right bottom`.
+fn parse_insets(value:&str)->Option<[f32;4]>{
+    let mut insets=[0f32;4];
+    let mut parts=value.split_whitespace();
+    for inset in insets.iter_mut(){
+        *inset=parts.next()?.parse().ok()?;
+    }
+
+    if parts.next().is_some(){
+        None
+    }
+    else{
+        Some(insets)
+    }
+}
+
+fn theme_error(key:&str,value:&str)->Error{
+    Error::new(ErrorKind::InvalidData,format!("theme: неверное значение '{}' для '{}'",value,key))
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn palette_refers_to_later_names(){
+        // `accent` раньше `light_blue` по алфавиту, но ссылается на него
+        let parsed=Theme::parse("palette.light_blue = #80C0FF\npalette.accent = light_blue\n").unwrap();
+        let accent=parsed.palette.iter().find(|entry|entry.0=="accent").unwrap().1;
+        assert_eq!(accent,parse_colour("#80C0FF",&[]).unwrap());
+
+        assert!(Theme::parse("palette.a = b\npalette.b = a\n").is_err());
+    }
+
+    #[test]
+    fn parses_dialogue_background(){
+        let parsed=Theme::parse("dialogue.image = dialogue_box\ndialogue.slice = 8 8 8 8\ndialogue.colour = #FFFFFF80\n").unwrap();
+        assert_eq!(parsed.dialogue_background.image.as_deref(),Some("dialogue_box"));
+        assert_eq!(parsed.dialogue_background.slice,[8f32;4]);
+        assert_eq!(parsed.dialogue_colour[3],128f32/255f32);
+
+        assert!(Theme::parse("dialogue.unknown = 1\n").is_err());
+    }
+}
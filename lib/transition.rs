@@ -0,0 +1,405 @@
+use crate::{
+    colours::White,
+    objects::{SimpleObject,TexturedObject},
+    scene::draw_on_texture,
+    viewport::gl_rect,
+};
+
+use cat_engine::{
+    Colour,
+    Window,
+    graphics::{Graphics,Graphics2D},
+    shapes::Rectangle,
+    texture::{ImageObject,Texture},
+    glium::{
+        Surface,
+        framebuffer::SimpleFrameBuffer,
+    },
+};
+
+use std::{
+    cell::RefCell,
+    mem::swap,
+};
+
+/// Направление перехода - куда уходит предыдущая страница.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum SwipeDirection{
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl SwipeDirection{
+    /// Единичный вектор направления.
+    pub fn vector(self)->[f32;2]{
+        match self{
+            SwipeDirection::Up=>[0f32,-1f32],
+            SwipeDirection::Down=>[0f32,1f32],
+            SwipeDirection::Left=>[-1f32,0f32],
+            SwipeDirection::Right=>[1f32,0f32],
+        }
+    }
+}
+
+/// Кривые сглаживания.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum Easing{
+    Linear,
+    /// Медленное начало
+    EaseIn,
+    /// Медленное окончание
+    EaseOut,
+    /// Медленные начало и окончание
+    EaseInOut,
+}
+
+impl Easing{
+    /// Сглаживание прогресса `t` от 0 до 1.
+    pub fn apply(self,t:f32)->f32{
+        let t=t.max(0f32).min(1f32);
+        match self{
+            Easing::Linear=>t,
+            Easing::EaseIn=>t*t,
+            Easing::EaseOut=>t*(2f32-t),
+            Easing::EaseInOut=>if t<0.5f32{
+                2f32*t*t
+            }
+            else{
+                1f32-2f32*(1f32-t)*(1f32-t)
+            }
+        }
+    }
+}
+
+/// Эффекты перехода.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum TransitionEffect{
+    /// Предыдущая страница уезжает, а открываемая выезжает за ней
+    Slide(SwipeDirection),
+    /// Затемнение предыдущей страницы до цвета и появление открываемой
+    FadeToColour(Colour),
+    /// Плавная смена прозрачности
+    CrossFade,
+    /// Открываемая страница открывается из-под предыдущей со стороны,
+    /// противоположной направлению
+    Wipe(SwipeDirection),
+}
+
+/// Состояние кадра перехода.
+///
+/// Сначала рисуется открываемая страница, затем предыдущая, затем заливка.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct TransitionFrame{
+    /// Сдвиг предыдущей страницы, `None` - не отрисовывается
+    pub previous:Option<[f32;2]>,
+    /// Непрозрачность предыдущей страницы
+    pub previous_alpha:f32,
+    /// Видимая часть предыдущей страницы [x,y,width,height]
+    pub previous_clip:Option<[f32;4]>,
+    /// Сдвиг открываемой страницы, `None` - не отрисовывается
+    pub next:Option<[f32;2]>,
+    /// Заливка поверх страниц
+    pub overlay:Option<Colour>,
+}
+
+impl TransitionEffect{
+    /// Состояние перехода при сглаженном прогрессе `progress`
+    /// для страницы размером `size`.
+    pub fn frame(self,progress:f32,size:[f32;2])->TransitionFrame{
+        let mut frame=TransitionFrame{
+            previous:Some([0f32;2]),
+            previous_alpha:1f32,
+            previous_clip:None,
+            next:Some([0f32;2]),
+            overlay:None,
+        };
+
+        match self{
+            TransitionEffect::Slide(direction)=>{
+                let [x,y]=direction.vector();
+                frame.previous=Some([
+                    x*size[0]*progress,
+                    y*size[1]*progress
+                ]);
+                frame.next=Some([
+                    -x*size[0]*(1f32-progress),
+                    -y*size[1]*(1f32-progress)
+                ]);
+            }
+
+            TransitionEffect::FadeToColour(mut colour)=>{
+                // Первая половина - затемнение, вторая - появление
+                if progress<0.5f32{
+                    frame.next=None;
+                    colour[3]*=progress*2f32;
+                }
+                else{
+                    frame.previous=None;
+                    colour[3]*=(1f32-progress)*2f32;
+                }
+                frame.overlay=Some(colour);
+            }
+
+            TransitionEffect::CrossFade=>{
+                frame.previous_alpha=1f32-progress;
+            }
+
+            TransitionEffect::Wipe(direction)=>{
+                let [width,height]=size;
+                let clip=match direction{
+                    SwipeDirection::Left=>[0f32,0f32,width*(1f32-progress),height],
+                    SwipeDirection::Right=>[width*progress,0f32,width*(1f32-progress),height],
+                    SwipeDirection::Up=>[0f32,0f32,width,height*(1f32-progress)],
+                    SwipeDirection::Down=>[0f32,height*progress,width,height*(1f32-progress)],
+                };
+                frame.previous_clip=Some(clip);
+            }
+        }
+
+        frame
+    }
+}
+
+/// Графические объекты для переходов, общие для всех страниц.
+pub struct TransitionObjects{
+    /// Отрисовка предыдущей страницы
+    pub snapshot:TexturedObject,
+    /// Текстура того же размера, на которую рисуется закрываемая страница.
+    /// Текстура объекта `snapshot` хранится в `Graphics2D`, и рисовать на неё,
+    /// пока `Graphics2D` используется для отрисовки, нельзя
+    target:RefCell<Texture>,
+    /// Заливка на весь экран
+    overlay:SimpleObject,
+    /// Размер страницы
+    size:[f32;2],
+}
+
+impl TransitionObjects{
+    /// `size` - размер страницы, `screen` - наибольший размер страницы
+    /// (размер экрана) для заливки.
+    pub fn new(snapshot:TexturedObject,target:Texture,size:[f32;2],screen:[f32;2],graphics:&mut Graphics2D)->TransitionObjects{
+        let overlay=Rectangle::new([0f32,0f32,screen[0],screen[1]],[0f32;4]);
+        Self{
+            snapshot,
+            target:RefCell::new(target),
+            overlay:SimpleObject::new(&overlay,graphics).unwrap(),
+            size,
+        }
+    }
+
+    /// Отрисовка закрываемой страницы для перехода.
+    ///
+    /// Страница рисуется на отдельную текстуру,
+    /// которая затем меняется местами с текстурой `snapshot`.
+    pub fn capture<F:FnOnce(&mut Graphics<SimpleFrameBuffer>)>(&self,window:&Window,graphics:&mut Graphics2D,f:F){
+        draw_on_texture(&self.target.borrow(),window,graphics,f);
+        swap(self.snapshot.texture(graphics),&mut *self.target.borrow_mut());
+    }
+
+    /// Изменение размера страницы.
+    pub fn resize(&mut self,size:[f32;2],graphics:&mut Graphics2D){
+        self.size=size;
+        let image=ImageObject::new([0f32,0f32,size[0],size[1]],[0f32,0f32,1f32,1f32],White);
+        self.snapshot.rewrite_vertices(&image,graphics);
+    }
+}
+
+/// Переход между страницами.
+pub struct Transition{
+    effect:TransitionEffect,
+    easing:Easing,
+    /// Продолжительность в секундах
+    duration:f32,
+    time:f32,
+}
+
+impl Transition{
+    /// Переход продолжительностью `duration` секунд.
+    pub fn new(effect:TransitionEffect,duration:f32)->Transition{
+        Self{
+            effect,
+            easing:Easing::Linear,
+            duration,
+            time:0f32,
+        }
+    }
+
+    pub fn easing(mut self,easing:Easing)->Transition{
+        self.easing=easing;
+        self
+    }
+
+    pub fn effect(&self)->TransitionEffect{
+        self.effect
+    }
+
+    /// Сглаженный прогресс от 0 до 1.
+    pub fn progress(&self)->f32{
+        if self.duration>0f32{
+            self.easing.apply(self.time/self.duration)
+        }
+        else{
+            1f32
+        }
+    }
+
+    /// Состояние текущего кадра.
+    pub fn frame(&self,size:[f32;2])->TransitionFrame{
+        self.effect.frame(self.progress(),size)
+    }
+
+    /// Продвижение перехода на `elapsed` секунд.
+    /// Возвращает `false`, когда переход завершён.
+    ///
+    /// Обновляет цвета объектов для переходов.
+    pub fn update(&mut self,elapsed:f32,objects:&TransitionObjects,graphics:&mut Graphics2D)->bool{
+        self.time=(self.time+elapsed).min(self.duration);
+
+        let finished=self.time>=self.duration;
+
+        let frame=self.frame(objects.size);
+        let mut colour=*objects.snapshot.colour(graphics);
+        colour[3]=if finished{1f32}else{frame.previous_alpha};
+        *objects.snapshot.colour(graphics)=colour;
+
+        *objects.overlay.colour(graphics)=frame.overlay.unwrap_or([0f32;4]);
+
+        !finished
+    }
+
+    /// Отрисовка перехода.
+    ///
+    /// `draw_next` рисует открываемую страницу с данным сдвигом.
+    pub fn draw<S,F>(&self,objects:&TransitionObjects,graphics:&mut Graphics<S>,draw_next:F)
+        where S:Surface,F:FnOnce([f32;2],&mut Graphics<S>)
+    {
+        let frame=self.frame(objects.size);
+
+        if let Some(shift)=frame.next{
+            draw_next(shift,graphics);
+        }
+
+        if let Some(shift)=frame.previous{
+            if let Some(clip)=frame.previous_clip{
+                graphics.draw_parameters.scissor=Some(gl_rect(clip,objects.size[1]));
+                objects.snapshot.draw_shift(shift,graphics);
+                graphics.draw_parameters.scissor=None;
+            }
+            else{
+                objects.snapshot.draw_shift(shift,graphics);
+            }
+        }
+
+        if frame.overlay.is_some(){
+            objects.overlay.draw(graphics);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    const size:[f32;2]=[800f32,600f32];
+
+    const easings:[Easing;4]=[Easing::Linear,Easing::EaseIn,Easing::EaseOut,Easing::EaseInOut];
+
+    #[test]
+    fn easing_endpoints(){
+        for &easing in easings.iter(){
+            assert_eq!(easing.apply(0f32),0f32);
+            assert_eq!(easing.apply(1f32),1f32);
+            // Прогресс за пределами 0..1 ограничивается
+            assert_eq!(easing.apply(-1f32),0f32);
+            assert_eq!(easing.apply(2f32),1f32);
+        }
+        assert_eq!(Easing::EaseInOut.apply(0.5f32),0.5f32);
+    }
+
+    #[test]
+    fn easing_monotonic(){
+        for &easing in easings.iter(){
+            let mut previous=easing.apply(0f32);
+            for step in 1..=100{
+                let value=easing.apply(step as f32/100f32);
+                assert!(value>=previous,"{:?} убывает на шаге {}",easing,step);
+                previous=value;
+            }
+        }
+    }
+
+    #[test]
+    fn slide_frames(){
+        let effect=TransitionEffect::Slide(SwipeDirection::Left);
+
+        let frame=effect.frame(0f32,size);
+        assert_eq!(frame.previous,Some([0f32,0f32]));
+        assert_eq!(frame.next,Some([800f32,0f32]));
+
+        let frame=effect.frame(0.5f32,size);
+        assert_eq!(frame.previous,Some([-400f32,0f32]));
+        assert_eq!(frame.next,Some([400f32,0f32]));
+
+        let frame=effect.frame(1f32,size);
+        assert_eq!(frame.previous,Some([-800f32,0f32]));
+        assert_eq!(frame.next,Some([0f32,0f32]));
+        assert_eq!(frame.overlay,None);
+    }
+
+    #[test]
+    fn fade_to_colour_frames(){
+        let effect=TransitionEffect::FadeToColour([0f32,0f32,0f32,1f32]);
+
+        let frame=effect.frame(0f32,size);
+        assert_eq!(frame.next,None);
+        assert_eq!(frame.overlay,Some([0f32,0f32,0f32,0f32]));
+
+        // Середина - полная заливка, видна только открываемая страница
+        let frame=effect.frame(0.5f32,size);
+        assert_eq!(frame.previous,None);
+        assert_eq!(frame.overlay,Some([0f32,0f32,0f32,1f32]));
+
+        let frame=effect.frame(1f32,size);
+        assert_eq!(frame.previous,None);
+        assert_eq!(frame.next,Some([0f32,0f32]));
+        assert_eq!(frame.overlay,Some([0f32,0f32,0f32,0f32]));
+    }
+
+    #[test]
+    fn cross_fade_frames(){
+        let effect=TransitionEffect::CrossFade;
+        assert_eq!(effect.frame(0f32,size).previous_alpha,1f32);
+        assert_eq!(effect.frame(0.5f32,size).previous_alpha,0.5f32);
+        assert_eq!(effect.frame(1f32,size).previous_alpha,0f32);
+        assert_eq!(effect.frame(0.5f32,size).next,Some([0f32,0f32]));
+    }
+
+    #[test]
+    fn wipe_frames(){
+        let effect=TransitionEffect::Wipe(SwipeDirection::Right);
+        assert_eq!(effect.frame(0f32,size).previous_clip,Some([0f32,0f32,800f32,600f32]));
+        assert_eq!(effect.frame(0.5f32,size).previous_clip,Some([400f32,0f32,400f32,600f32]));
+        assert_eq!(effect.frame(1f32,size).previous_clip,Some([800f32,0f32,0f32,600f32]));
+
+        let effect=TransitionEffect::Wipe(SwipeDirection::Up);
+        assert_eq!(effect.frame(0.5f32,size).previous_clip,Some([0f32,0f32,800f32,300f32]));
+    }
+
+    #[test]
+    fn transition_progress(){
+        let mut transition=Transition::new(TransitionEffect::CrossFade,2f32).easing(Easing::EaseIn);
+        assert_eq!(transition.progress(),0f32);
+
+        transition.time=1f32;
+        assert_eq!(transition.progress(),0.25f32);
+        assert_eq!(transition.frame(size).previous_alpha,0.75f32);
+
+        transition.time=2f32;
+        assert_eq!(transition.frame(size),TransitionEffect::CrossFade.frame(1f32,size));
+
+        // Переход нулевой продолжительности сразу завершён
+        assert_eq!(Transition::new(TransitionEffect::CrossFade,0f32).progress(),1f32);
+    }
+}
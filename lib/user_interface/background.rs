@@ -0,0 +1,286 @@
+use crate::{
+    colours::{Black,White},
+    objects::{SimpleObject,TextureObject,TexturedObject},
+};
+
+use cat_engine::{
+    Colour,
+    graphics::{Graphics,Graphics2D},
+    shapes::Rectangle,
+    texture::ImageObject,
+    image::RgbaImage,
+
+    glium::Surface,
+};
+
+use std::rc::Rc;
+
+/// Нарезка изображения на девять частей.
+///
+/// Углы рисуются без растяжения, края растягиваются вдоль,
+/// а центр - в обе стороны, поэтому одно изображение подходит
+/// для области любого размера.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct NineSlice{
+    /// Размер изображения в пикселях
+    image_size:[f32;2],
+    /// Неизменяемые края [left,top,right,bottom] в пикселях изображения
+    insets:[f32;4],
+    /// Размер пикселя изображения на экране
+    scale:f32,
+}
+
+impl NineSlice{
+    pub const fn new(image_size:[f32;2],insets:[f32;4])->NineSlice{
+        Self{
+            image_size,
+            insets,
+            scale:1f32,
+        }
+    }
+
+    pub fn scale(mut self,scale:f32)->NineSlice{
+        self.scale=scale;
+        self
+    }
+
+    /// Части [область,uv] для области `rect` по строкам сверху вниз.
+    ///
+    /// Если края не помещаются в область, они пропорционально уменьшаются.
+    pub fn parts(&self,[x,y,width,height]:[f32;4])->[([f32;4],[f32;4]);9]{
+        let [left,top,right,bottom]=self.insets;
+        let [image_width,image_height]=self.image_size;
+
+        let horizontal=(left+right)*self.scale;
+        let fit_x=if horizontal>width{width/horizontal}else{1f32};
+        let vertical=(top+bottom)*self.scale;
+        let fit_y=if vertical>height{height/vertical}else{1f32};
+
+        let xs=[
+            x,
+            x+left*self.scale*fit_x,
+            x+width-right*self.scale*fit_x,
+            x+width,
+        ];
+        let ys=[
+            y,
+            y+top*self.scale*fit_y,
+            y+height-bottom*self.scale*fit_y,
+            y+height,
+        ];
+
+        let us=[0f32,left/image_width,1f32-right/image_width,1f32];
+        let vs=[0f32,top/image_height,1f32-bottom/image_height,1f32];
+
+        let mut parts=[([0f32;4],[0f32;4]);9];
+        for row in 0..3{
+            for column in 0..3{
+                parts[row*3+column]=(
+                    [xs[column],ys[row],xs[column+1]-xs[column],ys[row+1]-ys[row]],
+                    [us[column],vs[row],us[column+1],vs[row+1]],
+                );
+            }
+        }
+        parts
+    }
+}
+
+/// Вид заднего фона виджета.
+#[derive(Clone)]
+pub enum BackgroundStyle{
+    /// Прямоугольник цвета фона с рамкой (ширина 0 - без рамки)
+    Flat{
+        border_width:f32,
+        border_colour:Colour,
+    },
+    /// Изображение, нарезанное на девять частей.
+    /// Цвет фона умножается на цвета изображения.
+    Image{
+        texture:Rc<TextureObject>,
+        slice:NineSlice,
+    },
+}
+
+impl BackgroundStyle{
+    /// Прямоугольник без рамки.
+    pub const fn flat()->BackgroundStyle{
+        BackgroundStyle::Flat{
+            border_width:0f32,
+            border_colour:Black,
+        }
+    }
+}
+
+enum Fill{
+    Flat(SimpleObject),
+    Image{
+        parts:Vec<TexturedObject>,
+        /// Текстура удерживается, пока есть части
+        _texture:Rc<TextureObject>,
+    },
+}
+
+/// Задний фон виджета.
+pub struct Background{
+    border:Option<SimpleObject>,
+    fill:Fill,
+}
+
+impl Background{
+    pub fn new(style:&BackgroundStyle,rect:[f32;4],colour:Colour,graphics:&mut Graphics2D)->Background{
+        match style{
+            BackgroundStyle::Flat{border_width,border_colour}=>{
+                let border_width=*border_width;
+
+                let border=if border_width>0f32{
+                    let border=Rectangle::new(rect,*border_colour);
+                    SimpleObject::new(&border,graphics)
+                }
+                else{
+                    None
+                };
+
+                let [x,y,width,height]=rect;
+                let inner=[
+                    x+border_width,
+                    y+border_width,
+                    (width-2f32*border_width).max(0f32),
+                    (height-2f32*border_width).max(0f32),
+                ];
+                let fill=Rectangle::new(inner,colour);
+
+                Self{
+                    border,
+                    fill:Fill::Flat(SimpleObject::new(&fill,graphics).unwrap()),
+                }
+            }
+
+            BackgroundStyle::Image{texture,slice}=>{
+                let parts=slice.parts(rect).iter().map(|&(part,uv)|{
+                    let image=ImageObject::raw_uv(part,uv,colour);
+                    TexturedObject::new(&image,texture,graphics).unwrap()
+                }).collect();
+
+                Self{
+                    border:None,
+                    fill:Fill::Image{
+                        parts,
+                        _texture:texture.clone(),
+                    },
+                }
+            }
+        }
+    }
+
+    /// Смена цвета фона (например, при нажатии).
+    pub fn set_colour(&self,colour:Colour,graphics:&mut Graphics2D){
+        match &self.fill{
+            Fill::Flat(fill)=>*fill.colour(graphics)=colour,
+            Fill::Image{parts,..}=>for part in parts{
+                *part.colour(graphics)=colour;
+            }
+        }
+    }
+
+    pub fn draw<S:Surface>(&self,graphics:&mut Graphics<S>){
+        self.draw_shift([0f32;2],graphics)
+    }
+
+    pub fn draw_shift<S:Surface>(&self,shift:[f32;2],graphics:&mut Graphics<S>){
+        if let Some(border)=&self.border{
+            border.draw_shift(shift,graphics);
+        }
+
+        match &self.fill{
+            Fill::Flat(fill)=>fill.draw_shift(shift,graphics),
+            Fill::Image{parts,..}=>for part in parts{
+                part.draw_shift(shift,graphics);
+            }
+        }
+    }
+}
+
+/// Изображение прямоугольника со скруглёнными углами для нарезки
+/// на девять частей: белая заливка и рамка цвета `border_colour`.
+///
+/// Размеры в пикселях.
+pub fn rounded_rect_image(radius:u32,border_width:u32,border_colour:Colour)->(RgbaImage,NineSlice){
+    let inset=radius.max(border_width).max(1u32);
+    let side=inset*2u32+1u32;
+
+    let half=side as f32/2f32;
+    let radius=radius as f32;
+    let border_width=border_width as f32;
+
+    let mut pixels=Vec::with_capacity((side*side*4u32) as usize);
+    for py in 0..side{
+        for px in 0..side{
+            // Расстояние со знаком от центра пикселя до края фигуры
+            let qx=(px as f32+0.5f32-half).abs()-(half-radius);
+            let qy=(py as f32+0.5f32-half).abs()-(half-radius);
+            let outside=(qx.max(0f32).powi(2)+qy.max(0f32).powi(2)).sqrt();
+            let distance=outside+qx.max(qy).min(0f32)-radius;
+
+            // Сглаживание края и перехода от заливки к рамке
+            let coverage=(0.5f32-distance).max(0f32).min(1f32);
+            let border=if border_width>0f32{
+                (distance+border_width+0.5f32).max(0f32).min(1f32)
+            }
+            else{
+                0f32
+            };
+
+            let mut colour=[0f32;4];
+            for c in 0..4{
+                colour[c]=White[c]+(border_colour[c]-White[c])*border;
+            }
+            colour[3]*=coverage;
+
+            for channel in colour.iter(){
+                pixels.push((channel*255f32).round() as u8);
+            }
+        }
+    }
+
+    let image=RgbaImage::from_raw(side,side,pixels).unwrap();
+    let inset=inset as f32;
+    let slice=NineSlice::new([side as f32;2],[inset;4]);
+
+    (image,slice)
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn nine_slice_keeps_corners(){
+        let slice=NineSlice::new([30f32,30f32],[10f32,10f32,10f32,10f32]);
+        let parts=slice.parts([100f32,100f32,200f32,60f32]);
+
+        assert_eq!(parts[0],([100f32,100f32,10f32,10f32],[0f32,0f32,1f32/3f32,1f32/3f32]));
+        assert_eq!(parts[4].0,[110f32,110f32,180f32,40f32]);
+        assert_eq!(parts[8].0,[290f32,150f32,10f32,10f32]);
+        assert_eq!(parts[8].1,[1f32-1f32/3f32,1f32-1f32/3f32,1f32,1f32]);
+    }
+
+    #[test]
+    fn nine_slice_scales_edges(){
+        let slice=NineSlice::new([40f32,20f32],[8f32,4f32,12f32,6f32]).scale(2f32);
+        let parts=slice.parts([0f32,0f32,100f32,100f32]);
+
+        assert_eq!(parts[0].0,[0f32,0f32,16f32,8f32]);
+        assert_eq!(parts[4].0,[16f32,8f32,60f32,80f32]);
+        assert_eq!(parts[8].0,[76f32,88f32,24f32,12f32]);
+    }
+
+    #[test]
+    fn nine_slice_shrinks_edges_to_fit(){
+        let slice=NineSlice::new([30f32,30f32],[10f32,10f32,10f32,10f32]);
+        let parts=slice.parts([0f32,0f32,10f32,40f32]);
+
+        assert_eq!(parts[0].0,[0f32,0f32,5f32,10f32]);
+        assert_eq!(parts[1].0,[5f32,0f32,0f32,10f32]);
+        assert_eq!(parts[2].0,[5f32,0f32,5f32,10f32]);
+    }
+}
@@ -1,11 +1,11 @@
-use crate::{
-    colours::{Black,Light_blue},
-};
+use crate::theme::Theme;
 
 use super::{
     TextView,
     TextViewSettings,
     GeneralSettings,
+    Background,
+    BackgroundStyle,
 };
 
 use cat_engine::{
@@ -14,14 +14,13 @@ use cat_engine::{
         Graphics2D,
         Graphics,
     },
-    shapes::Rectangle,
 
     glium::Surface,
 };
 
 pub struct Button{
     text:TextView,
-    background:usize,
+    background:Background,
     /// [x1,y1,x2,y2]
     click_area:[f32;4],
     pressed:bool,
@@ -31,12 +30,15 @@ impl Button{
     pub fn new<S:Into<String>>(settings:ButtonSettings<S>,graphics:&mut Graphics2D)->Button{
         let layout=settings.general.layout;
 
-        let rect=Rectangle::new(layout,settings.background_colour);
+        let background=Background::new(&settings.background,layout,settings.background_colour,graphics);
 
-        let text_view_settings=TextViewSettings::new(settings.text,settings.general)
-            .font_size(settings.font_size)
-            .font(settings.font)
-            .text_colour(settings.text_colour);
+        let text_view_settings=TextViewSettings::styled(
+            settings.text,
+            settings.general,
+            settings.font,
+            settings.font_size,
+            settings.text_colour
+        );
 
         let click_area=[
             layout[0],
@@ -47,14 +49,20 @@ impl Button{
 
         Self{
             text:TextView::new(text_view_settings,graphics),
-            background:graphics.add_simple_object(&rect).unwrap(),
+            background,
             click_area,
             pressed:false
         }
     }
 
-    pub fn background_index(&self)->usize{
-        self.background
+    /// Смена цвета заднего фона.
+    pub fn set_background_colour(&self,colour:Colour,graphics:&mut Graphics2D){
+        self.background.set_colour(colour,graphics)
+    }
+
+    /// Замена текста кнопки.
+    pub fn set_text<S:Into<String>>(&self,text:S,graphics:&mut Graphics2D){
+        self.text.set_text(text,graphics)
     }
 
     /// Проверяет находится ли точка в области кнопки.
@@ -86,38 +94,56 @@ impl Button{
     }
 
     pub fn draw<S:Surface>(&self,graphics:&mut Graphics<S>){
-        graphics.draw_simple_object(self.background).unwrap();
+        self.background.draw(graphics);
         self.text.draw(graphics);
     }
 
     pub fn draw_shift<S:Surface>(&self,shift:[f32;2],graphics:&mut Graphics<S>){
-        graphics.draw_shift_simple_object(self.background,shift).unwrap();
+        self.background.draw_shift(shift,graphics);
         self.text.draw_shift(shift,graphics);
     }
 }
 
 /// Настройки для построения кнопок
 pub struct ButtonSettings<S:Into<String>>{
-    general:GeneralSettings,
-    background_colour:Colour,
-    text:S,
-    font_size:f32,
-    font:usize,
-    text_colour:Colour
+    pub(super) general:GeneralSettings,
+    pub(super) background:BackgroundStyle,
+    pub(super) background_colour:Colour,
+    pub(super) text:S,
+    pub(super) font_size:f32,
+    pub(super) font:usize,
+    pub(super) text_colour:Colour
 }
 
 impl<S:Into<String>> ButtonSettings<S>{
-    pub fn new(text:S,rect:[f32;4])->ButtonSettings<S>{
+    pub fn new(text:S,rect:[f32;4],theme:&Theme)->ButtonSettings<S>{
         Self{
             general:GeneralSettings::new(rect),
-            background_colour:Light_blue,
+            background:theme.button_style.clone(),
+            background_colour:theme.button_colours.normal,
             text,
-            font_size:20f32,
-            font:0usize,
-            text_colour:Black,
+            font_size:theme.button_font_size,
+            font:theme.font,
+            text_colour:theme.button_text_colour,
         }
     }
 
+    pub fn background(mut self,background:BackgroundStyle)->ButtonSettings<S>{
+        self.background=background;
+        self
+    }
+
+    /// Рамка вокруг прямоугольного фона.
+    ///
+    /// Фон-изображение заменяется прямоугольником.
+    pub fn border(mut self,width:f32,colour:Colour)->ButtonSettings<S>{
+        self.background=BackgroundStyle::Flat{
+            border_width:width,
+            border_colour:colour,
+        };
+        self
+    }
+
     pub fn background_colour(mut self,colour:Colour)->ButtonSettings<S>{
         self.background_colour=colour;
         self
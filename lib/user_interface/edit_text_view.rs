@@ -1,16 +1,17 @@
 use crate::{
     Align,
     AlignX,
-    colours::{
-        Black,
-        White,
-    },
+    glyphs::cache_character,
+    log,
+    theme::Theme,
 };
 
 use super::{
     TextView,
     TextViewSettings,
     GeneralSettings,
+    Background,
+    BackgroundStyle,
 };
 
 use cat_engine::{
@@ -18,16 +19,15 @@ use cat_engine::{
     Colour,
     // structs
     graphics::{Graphics,Graphics2D},
-    shapes::Rectangle,
     text::RawGlyphCache,
 
-    glium::Surface,
+    glium::{Surface,Display},
 };
 
 // Изменяемый текстовый блок (возможность вписывать и удалять символы)
 pub struct EditTextView{
     text_view:TextView,
-    background:usize,
+    background:Background,
     click_area:[f32;4],
     capacity:usize,
     align:Align,
@@ -38,15 +38,15 @@ impl EditTextView{
         // Создание заднего фона
         let rect=settings.general.layout;
 
-        let text_view_settings=TextViewSettings::new(settings.text,settings.general)
-                .text_colour(settings.text_colour)
-                .font(settings.font)
-                .font_size(settings.font_size);
+        let text_view_settings=TextViewSettings::styled(
+            settings.text,
+            settings.general,
+            settings.font,
+            settings.font_size,
+            settings.text_colour
+        );
 
-        let background=Rectangle::new(rect,settings.background_colour);
-                //.border(2f32,settings.border_colour);
-
-        let background=graphics.add_simple_object(&background).unwrap();
+        let background=Background::new(&settings.background,rect,settings.background_colour,graphics);
 
         let click_area=[
             rect[0],
@@ -71,17 +71,26 @@ impl EditTextView{
     }
 
     pub fn text<'a>(&self,graphics:&'a mut Graphics2D)->&'a mut String{
-        graphics.get_text_object_text(self.text_view.index())
+        self.text_view.object().text(graphics)
     }
 
     /// Добавление символа с выравниванием.
-    pub fn push_char<'a>(&mut self,ch:char,graphics:&'a mut Graphics2D){
-        let font=*graphics.get_text_object_font(self.text_view.index());
+    ///
+    /// Если символа нет в кэше шрифта, он кэшируется.
+    pub fn push_char<'a>(&mut self,ch:char,display:&Display,graphics:&'a mut Graphics2D){
+        let font=self.text_view.object().font(graphics);
+
+        let scale=self.text_view.object().scale(graphics);
 
-        let scale=*graphics.get_text_object_scale(self.text_view.index());
+        // Управляющие символы (например, '\u{8}' от Backspace) не вводятся
+        if ch.is_control(){
+            return
+        }
 
-        if graphics.get_text_object_text(self.text_view.index()).len()<self.capacity{
-            graphics.get_text_object_text(self.text_view.index()).push(ch);
+        if self.text_view.object().text(graphics).chars().count()<self.capacity{
+            cache_character(ch,font,display,graphics);
+
+            self.text_view.object().text(graphics).push(ch);
             
             let character_width=graphics.get_font(font).text_width(&ch.to_string(),scale);
             
@@ -91,16 +100,16 @@ impl EditTextView{
                 AlignX::Left=>0f32,
             };
 
-            graphics.get_text_object_position(self.text_view.index())[0]-=dx; // Сдвиг по X
+            self.text_view.object().position(graphics)[0]-=dx; // Сдвиг по X
         }
     }
 
     /// Удаление последнего символа с выравниванием.
     pub fn pop_char<'a>(&mut self,graphics:&'a mut Graphics2D){
-        if let Some(ch)=graphics.get_text_object_text(self.text_view.index()).pop(){
-            let font=*graphics.get_text_object_font(self.text_view.index());
+        if let Some(ch)=self.text_view.object().text(graphics).pop(){
+            let font=self.text_view.object().font(graphics);
 
-            let scale=*graphics.get_text_object_scale(self.text_view.index());
+            let scale=self.text_view.object().scale(graphics);
 
             let character_width=graphics.get_font(font).text_width(&ch.to_string(),scale);
 
@@ -110,12 +119,12 @@ impl EditTextView{
                 AlignX::Left=>0f32,
             };
 
-            graphics.get_text_object_position(self.text_view.index())[0]+=dx; // Сдвиг по X
+            self.text_view.object().position(graphics)[0]+=dx; // Сдвиг по X
         }
     }
 
     pub fn draw<S:Surface>(&self,graphics:&mut Graphics<S>){
-        graphics.draw_simple_object(self.background).unwrap();
+        self.background.draw(graphics);
         self.text_view.draw(graphics)
     }
 }
@@ -128,22 +137,22 @@ pub struct EditTextViewSettings<S:Into<String>>{
     font:usize,
     text_colour:Colour,
     align:Align,
+    background:BackgroundStyle,
     background_colour:Colour,
-    border_colour:Colour,
 }
 
 impl<S:Into<String>> EditTextViewSettings<S>{
-    pub fn new(text:S,rect:[f32;4])->EditTextViewSettings<S>{
+    pub fn new(text:S,rect:[f32;4],theme:&Theme)->EditTextViewSettings<S>{
         Self{
             general:GeneralSettings::new(rect),
             text,
-            capacity:20usize,
-            font_size:20f32,
-            font:0usize,
-            text_colour:Black,
+            capacity:20usize, // В символах
+            font_size:theme.edit_font_size,
+            font:theme.font,
+            text_colour:theme.edit_text_colour,
             align:Align::center(),
-            background_colour:White,
-            border_colour:Black
+            background:theme.edit_style.clone(),
+            background_colour:theme.edit_background_colour,
         }
     }
 
@@ -152,8 +161,42 @@ impl<S:Into<String>> EditTextViewSettings<S>{
         self
     }
 
+    pub fn background(mut self,background:BackgroundStyle)->EditTextViewSettings<S>{
+        self.background=background;
+        self
+    }
+
+    /// Рамка вокруг прямоугольного фона.
+    ///
+    /// Фон-изображение заменяется прямоугольником.
+    pub fn border(mut self,width:f32,colour:Colour)->EditTextViewSettings<S>{
+        self.background=BackgroundStyle::Flat{
+            border_width:width,
+            border_colour:colour,
+        };
+        self
+    }
+
+    /// Цвет рамки прямоугольного фона.
+    ///
+    /// У фона-изображения рамка нарисована в самом изображении,
+    /// поэтому цвет не применяется (с предупреждением в журнале).
     pub fn border_colour(mut self,colour:Colour)->EditTextViewSettings<S>{
-        self.border_colour=colour;
+        match &mut self.background{
+            BackgroundStyle::Flat{border_colour,..}=>*border_colour=colour,
+            BackgroundStyle::Image{..}=>log::warning("цвет рамки не применяется к фону-изображению"),
+        }
+        self
+    }
+
+    pub fn font_size(mut self,size:f32)->EditTextViewSettings<S>{
+        self.font_size=size;
+        self
+    }
+
+    /// Максимальное количество символов.
+    pub fn capacity(mut self,capacity:usize)->EditTextViewSettings<S>{
+        self.capacity=capacity;
         self
     }
 
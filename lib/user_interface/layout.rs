@@ -0,0 +1,437 @@
+use crate::{
+    Align,
+    AlignX,
+    AlignY,
+    viewport::Anchor,
+};
+
+use super::GeneralSettings;
+
+/// Внутренние отступы контейнера.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct Padding{
+    pub left:f32,
+    pub top:f32,
+    pub right:f32,
+    pub bottom:f32,
+}
+
+impl Padding{
+    pub const fn new(left:f32,top:f32,right:f32,bottom:f32)->Padding{
+        Self{
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    pub const fn zero()->Padding{
+        Padding::all(0f32)
+    }
+
+    /// Одинаковый отступ со всех сторон.
+    pub const fn all(padding:f32)->Padding{
+        Padding::new(padding,padding,padding,padding)
+    }
+
+    /// Отступы по горизонтали (слева и справа) и по вертикали (сверху и снизу).
+    pub const fn symmetric(horizontal:f32,vertical:f32)->Padding{
+        Padding::new(horizontal,vertical,horizontal,vertical)
+    }
+
+    /// Область [x,y,width,height] без отступов.
+    pub fn apply(&self,[x,y,width,height]:[f32;4])->[f32;4]{
+        [
+            x+self.left,
+            y+self.top,
+            (width-self.left-self.right).max(0f32),
+            (height-self.top-self.bottom).max(0f32),
+        ]
+    }
+}
+
+/// Сдвиг по оси для выравнивания содержимого длиной `size`
+/// в промежутке длиной `length`.
+fn align_x(align:&AlignX,length:f32,size:f32)->f32{
+    match align{
+        AlignX::Left=>0f32,
+        AlignX::Center=>(length-size)/2f32,
+        AlignX::Right=>length-size,
+    }
+}
+
+fn align_y(align:&AlignY,length:f32,size:f32)->f32{
+    match align{
+        AlignY::Up=>0f32,
+        AlignY::Center=>(length-size)/2f32,
+        AlignY::Down=>length-size,
+    }
+}
+
+/// Вертикальный ряд.
+///
+/// Элементы располагаются сверху вниз через `spacing`.
+/// `align.x` выравнивает каждый элемент по ширине ряда,
+/// `align.y` - весь ряд по высоте области.
+#[derive(Clone)]
+pub struct VStack{
+    general:GeneralSettings,
+    padding:Padding,
+    spacing:f32,
+    align:Align,
+}
+
+impl VStack{
+    pub fn new(general:GeneralSettings)->VStack{
+        Self{
+            general,
+            padding:Padding::zero(),
+            spacing:0f32,
+            align:Align::center(),
+        }
+    }
+
+    pub fn padding(mut self,padding:Padding)->VStack{
+        self.padding=padding;
+        self
+    }
+
+    /// Расстояние между элементами.
+    pub fn spacing(mut self,spacing:f32)->VStack{
+        self.spacing=spacing;
+        self
+    }
+
+    pub fn align_x(mut self,align:AlignX)->VStack{
+        self.align.x=align;
+        self
+    }
+
+    pub fn align_y(mut self,align:AlignY)->VStack{
+        self.align.y=align;
+        self
+    }
+
+    /// Размер ряда из элементов с размерами `sizes` (без отступов).
+    pub fn size(&self,sizes:&[[f32;2]])->[f32;2]{
+        let width=sizes.iter().fold(0f32,|width,size|width.max(size[0]));
+        let height=sizes.iter().map(|size|size[1]).sum::<f32>()
+                +self.spacing*sizes.len().saturating_sub(1) as f32;
+        [width,height]
+    }
+
+    /// Области [x,y,width,height] для элементов с размерами `sizes`.
+    pub fn rects(&self,sizes:&[[f32;2]])->Vec<[f32;4]>{
+        let [x,y,width,height]=self.padding.apply(self.general.layout);
+        let [_,stack_height]=self.size(sizes);
+
+        let mut top=y+align_y(&self.align.y,height,stack_height);
+
+        let mut rects=Vec::with_capacity(sizes.len());
+        for &[item_width,item_height] in sizes{
+            let left=x+align_x(&self.align.x,width,item_width);
+            rects.push([left,top,item_width,item_height]);
+            top+=item_height+self.spacing;
+        }
+        rects
+    }
+}
+
+/// Горизонтальный ряд.
+///
+/// Элементы располагаются слева направо через `spacing`.
+/// `align.y` выравнивает каждый элемент по высоте ряда,
+/// `align.x` - весь ряд по ширине области.
+#[derive(Clone)]
+pub struct HStack{
+    general:GeneralSettings,
+    padding:Padding,
+    spacing:f32,
+    align:Align,
+}
+
+impl HStack{
+    pub fn new(general:GeneralSettings)->HStack{
+        Self{
+            general,
+            padding:Padding::zero(),
+            spacing:0f32,
+            align:Align::center(),
+        }
+    }
+
+    pub fn padding(mut self,padding:Padding)->HStack{
+        self.padding=padding;
+        self
+    }
+
+    /// Расстояние между элементами.
+    pub fn spacing(mut self,spacing:f32)->HStack{
+        self.spacing=spacing;
+        self
+    }
+
+    pub fn align_x(mut self,align:AlignX)->HStack{
+        self.align.x=align;
+        self
+    }
+
+    pub fn align_y(mut self,align:AlignY)->HStack{
+        self.align.y=align;
+        self
+    }
+
+    /// Размер ряда из элементов с размерами `sizes` (без отступов).
+    pub fn size(&self,sizes:&[[f32;2]])->[f32;2]{
+        let width=sizes.iter().map(|size|size[0]).sum::<f32>()
+                +self.spacing*sizes.len().saturating_sub(1) as f32;
+        let height=sizes.iter().fold(0f32,|height,size|height.max(size[1]));
+        [width,height]
+    }
+
+    /// Области [x,y,width,height] для элементов с размерами `sizes`.
+    pub fn rects(&self,sizes:&[[f32;2]])->Vec<[f32;4]>{
+        let [x,y,width,height]=self.padding.apply(self.general.layout);
+        let [stack_width,_]=self.size(sizes);
+
+        let mut left=x+align_x(&self.align.x,width,stack_width);
+
+        let mut rects=Vec::with_capacity(sizes.len());
+        for &[item_width,item_height] in sizes{
+            let top=y+align_y(&self.align.y,height,item_height);
+            rects.push([left,top,item_width,item_height]);
+            left+=item_width+self.spacing;
+        }
+        rects
+    }
+}
+
+/// Сетка одинаковых ячеек.
+///
+/// Ячейки заполняются по строкам слева направо,
+/// вся сетка выравнивается в области по `align`.
+#[derive(Clone)]
+pub struct Grid{
+    general:GeneralSettings,
+    padding:Padding,
+    /// Расстояние между ячейками [по горизонтали,по вертикали]
+    spacing:[f32;2],
+    align:Align,
+    columns:usize,
+    cell_size:[f32;2],
+}
+
+impl Grid{
+    pub fn new(general:GeneralSettings,columns:usize,cell_size:[f32;2])->Grid{
+        Self{
+            general,
+            padding:Padding::zero(),
+            spacing:[0f32;2],
+            align:Align::center(),
+            columns:columns.max(1),
+            cell_size,
+        }
+    }
+
+    pub fn padding(mut self,padding:Padding)->Grid{
+        self.padding=padding;
+        self
+    }
+
+    pub fn spacing(mut self,horizontal:f32,vertical:f32)->Grid{
+        self.spacing=[horizontal,vertical];
+        self
+    }
+
+    pub fn align_x(mut self,align:AlignX)->Grid{
+        self.align.x=align;
+        self
+    }
+
+    pub fn align_y(mut self,align:AlignY)->Grid{
+        self.align.y=align;
+        self
+    }
+
+    /// Количество строк для `count` ячеек.
+    pub fn rows(&self,count:usize)->usize{
+        (count+self.columns-1)/self.columns
+    }
+
+    /// Размер сетки из `count` ячеек (без отступов).
+    pub fn size(&self,count:usize)->[f32;2]{
+        let columns=count.min(self.columns);
+        let rows=self.rows(count);
+        [
+            self.cell_size[0]*columns as f32+self.spacing[0]*columns.saturating_sub(1) as f32,
+            self.cell_size[1]*rows as f32+self.spacing[1]*rows.saturating_sub(1) as f32,
+        ]
+    }
+
+    /// Области [x,y,width,height] для `count` ячеек.
+    pub fn rects(&self,count:usize)->Vec<[f32;4]>{
+        let [x,y,width,height]=self.padding.apply(self.general.layout);
+        let [grid_width,grid_height]=self.size(count);
+
+        let left=x+align_x(&self.align.x,width,grid_width);
+        let top=y+align_y(&self.align.y,height,grid_height);
+
+        let [cell_width,cell_height]=self.cell_size;
+
+        (0..count).map(|index|{
+            let column=(index%self.columns) as f32;
+            let row=(index/self.columns) as f32;
+            [
+                left+(cell_width+self.spacing[0])*column,
+                top+(cell_height+self.spacing[1])*row,
+                cell_width,
+                cell_height,
+            ]
+        }).collect()
+    }
+}
+
+/// Привязка элементов к краям, углам или центру области.
+#[derive(Clone)]
+pub struct AnchorBox{
+    general:GeneralSettings,
+    padding:Padding,
+}
+
+impl AnchorBox{
+    pub fn new(general:GeneralSettings)->AnchorBox{
+        Self{
+            general,
+            padding:Padding::zero(),
+        }
+    }
+
+    pub fn padding(mut self,padding:Padding)->AnchorBox{
+        self.padding=padding;
+        self
+    }
+
+    /// Область элемента размером `size`, привязанного точкой `anchor`
+    /// со сдвигом `offset`.
+    pub fn rect(&self,anchor:Anchor,offset:[f32;2],size:[f32;2])->[f32;4]{
+        anchor.place(self.padding.apply(self.general.layout),offset,size)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    const area:[f32;4]=[100f32,50f32,400f32,300f32];
+
+    #[test]
+    fn padding_shrinks_area(){
+        assert_eq!(Padding::new(10f32,20f32,30f32,40f32).apply(area),[110f32,70f32,360f32,240f32]);
+        assert_eq!(Padding::all(300f32).apply(area),[400f32,350f32,0f32,0f32]);
+    }
+
+    #[test]
+    fn vstack_centered(){
+        let stack=VStack::new(GeneralSettings::new(area)).spacing(10f32);
+        let rects=stack.rects(&[[100f32,40f32],[200f32,60f32]]);
+
+        assert_eq!(stack.size(&[[100f32,40f32],[200f32,60f32]]),[200f32,110f32]);
+        assert_eq!(rects,vec![
+            [250f32,145f32,100f32,40f32],
+            [200f32,195f32,200f32,60f32],
+        ]);
+    }
+
+    #[test]
+    fn vstack_aligned_with_padding(){
+        let stack=VStack::new(GeneralSettings::new(area))
+                .padding(Padding::all(10f32))
+                .spacing(5f32)
+                .align_x(AlignX::Right)
+                .align_y(AlignY::Down);
+        let rects=stack.rects(&[[50f32,20f32],[80f32,20f32]]);
+
+        assert_eq!(rects,vec![
+            [440f32,295f32,50f32,20f32],
+            [410f32,320f32,80f32,20f32],
+        ]);
+    }
+
+    #[test]
+    fn hstack_left_top(){
+        let stack=HStack::new(GeneralSettings::new(area))
+                .padding(Padding::symmetric(20f32,10f32))
+                .spacing(15f32)
+                .align_x(AlignX::Left)
+                .align_y(AlignY::Up);
+        let rects=stack.rects(&[[60f32,30f32],[40f32,50f32]]);
+
+        assert_eq!(stack.size(&[[60f32,30f32],[40f32,50f32]]),[115f32,50f32]);
+        assert_eq!(rects,vec![
+            [120f32,60f32,60f32,30f32],
+            [195f32,60f32,40f32,50f32],
+        ]);
+    }
+
+    #[test]
+    fn hstack_centered(){
+        let stack=HStack::new(GeneralSettings::new(area)).spacing(20f32);
+        let rects=stack.rects(&[[100f32,100f32],[100f32,50f32]]);
+
+        assert_eq!(rects,vec![
+            [190f32,150f32,100f32,100f32],
+            [310f32,175f32,100f32,50f32],
+        ]);
+    }
+
+    #[test]
+    fn empty_stacks(){
+        let stack=VStack::new(GeneralSettings::new(area)).spacing(10f32);
+        assert_eq!(stack.size(&[]),[0f32,0f32]);
+        assert!(stack.rects(&[]).is_empty());
+
+        let stack=HStack::new(GeneralSettings::new(area)).spacing(10f32);
+        assert_eq!(stack.size(&[]),[0f32,0f32]);
+        assert!(stack.rects(&[]).is_empty());
+    }
+
+    #[test]
+    fn grid_fills_rows(){
+        let grid=Grid::new(GeneralSettings::new(area),3,[100f32,50f32])
+                .spacing(10f32,20f32)
+                .align_x(AlignX::Left)
+                .align_y(AlignY::Up);
+
+        assert_eq!(grid.rows(5),2);
+        assert_eq!(grid.size(5),[320f32,120f32]);
+        assert_eq!(grid.rects(5),vec![
+            [100f32,50f32,100f32,50f32],
+            [210f32,50f32,100f32,50f32],
+            [320f32,50f32,100f32,50f32],
+            [100f32,120f32,100f32,50f32],
+            [210f32,120f32,100f32,50f32],
+        ]);
+    }
+
+    #[test]
+    fn grid_centered(){
+        let grid=Grid::new(GeneralSettings::new(area),4,[40f32,40f32]).spacing(10f32,10f32);
+
+        // Неполная строка - ширина по числу ячеек
+        assert_eq!(grid.size(2),[90f32,40f32]);
+        assert_eq!(grid.rects(2),vec![
+            [255f32,180f32,40f32,40f32],
+            [305f32,180f32,40f32,40f32],
+        ]);
+    }
+
+    #[test]
+    fn anchor_box_places_in_corners(){
+        let anchor=AnchorBox::new(GeneralSettings::new(area)).padding(Padding::all(10f32));
+
+        assert_eq!(anchor.rect(Anchor::TopLeft,[0f32;2],[50f32,20f32]),[110f32,60f32,50f32,20f32]);
+        assert_eq!(anchor.rect(Anchor::BottomRight,[-5f32,-5f32],[50f32,20f32]),[435f32,315f32,50f32,20f32]);
+        assert_eq!(anchor.rect(Anchor::Center,[0f32,10f32],[100f32,100f32]),[250f32,160f32,100f32,100f32]);
+    }
+}
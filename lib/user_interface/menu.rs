@@ -2,10 +2,7 @@ use crate::{
     Align,
     AlignX,
     AlignY,
-    colours::{
-        White,
-        Light_blue,
-    }
+    theme::Theme,
 };
 
 use super::{
@@ -13,7 +10,9 @@ use super::{
     TextViewSettings,
     Button,
     ButtonSettings,
+    BackgroundStyle,
     GeneralSettings,
+    VStack,
 };
 
 use cat_engine::{
@@ -27,10 +26,6 @@ use cat_engine::{
     glium::Surface,
 };
 
-const head_margin:f32=50f32; // Расстояние между заголовком и кнопками
-const button_margin:f32=10f32; // Расстояние между кнопками
-const dmargin:f32=head_margin-button_margin; // Для расчёта высоты меню - чтобы не вычитать button_margin
-
 pub struct Menu{
     header:TextView,
     buttons:Vec<Button>,
@@ -44,73 +39,51 @@ impl Menu{
     )->Menu{
         let buttons_text:Vec<String>=settings.buttons_text.into_iter().map(|t|t.into()).collect();
 
-        let x0=settings.general.layout[0];        //
-        let y0=settings.general.layout[1];        // Положение и размер
-        let width=settings.general.layout[2];     // области для вставки
-        let height=settings.general.layout[3];    //
-
-        // Полная высота меню
-        let menu_height=settings.header_size[1]+dmargin+(settings.buttons_size[1]+button_margin)*buttons_text.len() as f32;
-
-        // Положение заголовка по Y
-        let mut y=match settings.align.y{
-            AlignY::Up=>y0,
-            AlignY::Center=>y0+(height-menu_height)/2f32,
-            AlignY::Down=>y0+height-menu_height,
-        };
+        // Ряд кнопок
+        let button_sizes=vec![settings.buttons_size;buttons_text.len()];
+        let buttons_stack=VStack::new(settings.general.clone())
+                .spacing(settings.button_spacing)
+                .align_x(settings.align.x.clone());
 
-        // Положение заголовка по X
-        let mut x=match settings.align.x{
-            AlignX::Right=>x0+width-settings.header_size[0],
-            AlignX::Center=>x0+(width-settings.header_size[0])/2f32,
-            AlignX::Left=>x0,
-        };
+        // Заголовок над рядом кнопок
+        let menu_rects=VStack::new(settings.general)
+                .spacing(settings.header_spacing)
+                .align_x(settings.align.x.clone())
+                .align_y(settings.align.y.clone())
+                .rects(&[settings.header_size,buttons_stack.size(&button_sizes)]);
 
         // Настройки для заголовка
-        let head_settings=TextViewSettings::new(
+        let head_settings=TextViewSettings::styled(
             settings.header_text,
-            GeneralSettings::new([
-                x,
-                y,
-                settings.header_size[0],
-                settings.header_size[1]
-            ]))
-            .align_x(settings.align.x.clone())
-            .font_size(settings.header_font_size)
-            .font(settings.font)
-            .text_colour(settings.header_text_colour);
-
-        // Положение верней кнопки по Y
-        y+=settings.header_size[1]+head_margin;
-
-        // Положение кнопок по X
-        x=match settings.align.x{
-            AlignX::Right=>x0+width-settings.buttons_size[0],
-            AlignX::Center=>x0+(width-settings.buttons_size[0])/2f32,
-            AlignX::Left=>x0,
-        };
-
-        // Массив кнопок
-        let mut buttons=Vec::with_capacity(buttons_text.len());
-
-        // Положение и размер кнопок
-        let mut button_rect=[
-            x,
-            y,
-            settings.buttons_size[0],
-            settings.buttons_size[1]
-        ];
+            GeneralSettings::new(menu_rects[0]),
+            settings.font,
+            settings.header_font_size,
+            settings.header_text_colour
+        )
+            .align_x(settings.align.x.clone());
+
+        // Положение кнопок в отведённой им области
+        let button_rects=VStack::new(GeneralSettings::new(menu_rects[1]))
+                .spacing(settings.button_spacing)
+                .align_x(settings.align.x.clone())
+                .align_y(AlignY::Up)
+                .rects(&button_sizes);
 
         // Создание кнопок
-        for text in buttons_text.into_iter(){
-            let button_sets=ButtonSettings::<String>::new(text,button_rect)
-                    .background_colour(settings.buttons_colour)
-                    .font(settings.font)
-                    .font_size(settings.buttons_font_size);
+        let mut buttons=Vec::with_capacity(buttons_text.len());
+        for (text,button_rect) in buttons_text.into_iter().zip(button_rects){
+            let button_sets=ButtonSettings{
+                general:GeneralSettings::new(button_rect),
+                background:settings.buttons_background.clone(),
+                background_colour:settings.buttons_colour,
+                text,
+                font_size:settings.buttons_font_size,
+                font:settings.font,
+                text_colour:settings.buttons_text_colour,
+            };
 
             let button=Button::new(button_sets,graphics);
             buttons.push(button);
-            button_rect[1]+=settings.buttons_size[1]+button_margin;
         }
 
         Self{
@@ -120,14 +93,20 @@ impl Menu{
         }
     }
 
-    pub fn button_index(&self,index:usize)->usize{
-        self.buttons[index].background_index()
+    /// Смена цвета заднего фона кнопки.
+    pub fn set_button_colour(&self,index:usize,colour:Colour,graphics:&mut Graphics2D){
+        self.buttons[index].set_background_colour(colour,graphics)
     }
 
     pub fn pressed_button(&self)->Option<usize>{
         self.pressed_button
     }
 
+    /// Кнопка под точкой (порядковый номер в меню).
+    pub fn button_at(&self,x:f32,y:f32)->Option<usize>{
+        self.buttons.iter().position(|button|button.in_area(x,y))
+    }
+
     /// Возвращает порядковый номер в меню.
     pub fn pressed(&mut self,x:f32,y:f32)->Option<usize>{
         self.pressed_button=None;
@@ -175,29 +154,37 @@ pub struct MenuSettings<S:Into<String>,BS:Into<String>,B:Iterator<Item=BS>>{
     align:Align, // Выравнивание меню
     header_text:S, // Текст заголовка меню
     header_size:[f32;2], // Ширина и высота заголовка
+    header_spacing:f32, // Расстояние между заголовком и кнопками
     font:usize, // Номер сохранённого шрифта
     header_font_size:f32,
     header_text_colour:Colour,
     buttons_size:[f32;2], // [width,height], по умолчанию [100, 60]
+    button_spacing:f32, // Расстояние между кнопками
     buttons_text:B,
     buttons_font_size:f32,
     buttons_colour:Colour,
+    buttons_text_colour:Colour,
+    buttons_background:BackgroundStyle,
 }
 
 impl<S:Into<String>,BS:Into<String>,B:Iterator<Item=BS>> MenuSettings<S,BS,B>{
-    pub fn new(head:S,buttons:B)->MenuSettings<S,BS,B>{
+    pub fn new(head:S,buttons:B,theme:&Theme)->MenuSettings<S,BS,B>{
         Self{
             general:GeneralSettings::new(window_rect()),
             header_text:head,
-            header_size:[100f32,60f32],
-            font:0usize,
-            header_font_size:40f32,
-            header_text_colour:White,
+            header_size:theme.header_size,
+            header_spacing:theme.header_spacing,
+            font:theme.font,
+            header_font_size:theme.header_font_size,
+            header_text_colour:theme.header_colour,
             align:Align::center(),
-            buttons_size:[100f32,60f32],
+            buttons_size:theme.button_size,
+            button_spacing:theme.button_spacing,
             buttons_text:buttons,
-            buttons_font_size:18f32,
-            buttons_colour:Light_blue,
+            buttons_font_size:theme.button_font_size,
+            buttons_colour:theme.button_colours.normal,
+            buttons_text_colour:theme.button_text_colour,
+            buttons_background:theme.button_style.clone(),
         }
     }
 
@@ -211,6 +198,11 @@ impl<S:Into<String>,BS:Into<String>,B:Iterator<Item=BS>> MenuSettings<S,BS,B>{
         self
     }
 
+    pub fn header_spacing(mut self,spacing:f32)->MenuSettings<S,BS,B>{
+        self.header_spacing=spacing;
+        self
+    }
+
     pub fn header_font_size(mut self,font_size:f32)->MenuSettings<S,BS,B>{
         self.header_font_size=font_size;
         self
@@ -231,6 +223,11 @@ impl<S:Into<String>,BS:Into<String>,B:Iterator<Item=BS>> MenuSettings<S,BS,B>{
         self
     }
 
+    pub fn button_spacing(mut self,spacing:f32)->MenuSettings<S,BS,B>{
+        self.button_spacing=spacing;
+        self
+    }
+
     pub fn button_font_size(mut self,size:f32)->MenuSettings<S,BS,B>{
         self.buttons_font_size=size;
         self
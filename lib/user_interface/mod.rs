@@ -16,12 +16,41 @@ pub use edit_text_view::{
     EditTextViewSettings
 };
 
+mod progress_bar;
+pub use progress_bar::{
+    ProgressBar,
+    ProgressBarSettings
+};
+
+mod toast;
+pub use toast::{
+    Toast,
+    ToastSettings
+};
+
 mod menu;
 pub use menu::{
     Menu,
     MenuSettings
 };
 
+mod background;
+pub use background::{
+    NineSlice,
+    BackgroundStyle,
+    Background,
+    rounded_rect_image
+};
+
+mod layout;
+pub use layout::{
+    Padding,
+    VStack,
+    HStack,
+    Grid,
+    AnchorBox
+};
+
 #[derive(Clone)]
 pub struct GeneralSettings{
     /// Область для вставки объекта
@@ -0,0 +1,101 @@
+use crate::{
+    objects::SimpleObject,
+    theme::Theme,
+    viewport::gl_rect,
+};
+
+use super::GeneralSettings;
+
+use cat_engine::{
+    // statics
+    window_height,
+    // types
+    Colour,
+    // structs
+    graphics::{Graphics,Graphics2D},
+    shapes::Rectangle,
+
+    glium::Surface,
+};
+
+/// Полоса хода загрузки.
+pub struct ProgressBar{
+    track:SimpleObject,
+    fill:SimpleObject,
+    rect:[f32;4],
+    /// Доля заполнения от 0 до 1
+    progress:f32,
+}
+
+impl ProgressBar{
+    pub fn new(settings:ProgressBarSettings,graphics:&mut Graphics2D)->ProgressBar{
+        let rect=settings.general.layout;
+
+        let track=Rectangle::new(rect,settings.track_colour);
+        let fill=Rectangle::new(rect,settings.fill_colour);
+
+        Self{
+            track:SimpleObject::new(&track,graphics).unwrap(),
+            fill:SimpleObject::new(&fill,graphics).unwrap(),
+            rect,
+            progress:0f32,
+        }
+    }
+
+    pub fn progress(&self)->f32{
+        self.progress
+    }
+
+    pub fn set_progress(&mut self,progress:f32){
+        self.progress=progress.max(0f32).min(1f32);
+    }
+
+    /// Цвет заполненной части.
+    pub fn fill_colour<'a>(&self,graphics:&'a mut Graphics2D)->&'a mut Colour{
+        self.fill.colour(graphics)
+    }
+
+    pub fn draw<S:Surface>(&self,graphics:&mut Graphics<S>){
+        self.draw_shift([0f32;2],graphics)
+    }
+
+    pub fn draw_shift<S:Surface>(&self,shift:[f32;2],graphics:&mut Graphics<S>){
+        self.track.draw_shift(shift,graphics);
+
+        // Заполненная часть обрезается по доле загрузки
+        let [x,y,width,height]=self.rect;
+        let clip=[x+shift[0],y+shift[1],width*self.progress,height];
+
+        let scissor=graphics.draw_parameters.scissor;
+        graphics.draw_parameters.scissor=Some(gl_rect(clip,unsafe{window_height}));
+        self.fill.draw_shift(shift,graphics);
+        graphics.draw_parameters.scissor=scissor;
+    }
+}
+
+/// Настройки полосы загрузки
+pub struct ProgressBarSettings{
+    general:GeneralSettings,
+    track_colour:Colour,
+    fill_colour:Colour,
+}
+
+impl ProgressBarSettings{
+    pub fn new(rect:[f32;4],theme:&Theme)->ProgressBarSettings{
+        Self{
+            general:GeneralSettings::new(rect),
+            track_colour:theme.progress_track_colour,
+            fill_colour:theme.progress_fill_colour,
+        }
+    }
+
+    pub fn track_colour(mut self,colour:Colour)->ProgressBarSettings{
+        self.track_colour=colour;
+        self
+    }
+
+    pub fn fill_colour(mut self,colour:Colour)->ProgressBarSettings{
+        self.fill_colour=colour;
+        self
+    }
+}
@@ -2,13 +2,13 @@ use crate::{
     Align,
     AlignX,
     AlignY,
-    colours::{
-        Black,
-    },
+    theme::Theme,
 };
 
 use super::GeneralSettings;
 
+use crate::objects::TextObject;
+
 use cat_engine::{
     Colour,
     text::{
@@ -23,9 +23,10 @@ use cat_engine::{
     glium::Surface,
 };
 
-#[derive(Clone)]
 pub struct TextView{
-    index:usize,
+    object:TextObject,
+    layout:[f32;4],
+    align:Align,
 }
 
 impl TextView{
@@ -45,20 +46,37 @@ impl TextView{
         let text_base=TextBase::new([x,y],scale,settings.text_colour);
 
         Self{
-            index:graphics.add_text_object(line,&text_base,settings.font).unwrap(),
+            object:TextObject::new(line,&text_base,settings.font,graphics).unwrap(),
+            layout:settings.general.layout,
+            align:settings.align,
         }
     }
 
-    pub fn index(&self)->usize{
-        self.index
+    pub fn object(&self)->&TextObject{
+        &self.object
+    }
+
+    /// Замена текста с сохранением выравнивания.
+    pub fn set_text<S:Into<String>>(&self,text:S,graphics:&mut Graphics2D){
+        let line=text.into();
+
+        let font=self.object.font(graphics);
+        let scale=self.object.scale(graphics);
+
+        let text_size=graphics.get_font(font).text_size(&line,scale);
+
+        let (x,y)=self.align.text_position(self.layout,text_size);
+
+        *self.object.position(graphics)=[x,y];
+        *self.object.text(graphics)=line;
     }
 
     pub fn draw<S:Surface>(&self,graphics:&mut Graphics<S>){
-        graphics.draw_text_object(self.index).unwrap();
+        self.object.draw(graphics);
     }
 
     pub fn draw_shift<S:Surface>(&self,shift:[f32;2],graphics:&mut Graphics<S>){
-        graphics.draw_shift_text_object(self.index,shift).unwrap();
+        self.object.draw_shift(shift,graphics);
     }
 }
 
@@ -74,13 +92,19 @@ pub struct TextViewSettings<S:Into<String>>{
 }
 
 impl<S:Into<String>> TextViewSettings<S>{
-    pub fn new(text:S,general:GeneralSettings)->TextViewSettings<S>{
+    /// Настройки с шрифтом и цветом текста из оформления.
+    pub fn new(text:S,general:GeneralSettings,theme:&Theme)->TextViewSettings<S>{
+        Self::styled(text,general,theme.font,theme.font_size,theme.text_colour)
+    }
+
+    /// Настройки для текста внутри других виджетов, которые берут шрифт из своих настроек.
+    pub(super) fn styled(text:S,general:GeneralSettings,font:usize,font_size:f32,text_colour:Colour)->TextViewSettings<S>{
         Self{
             general,
             text,
-            font_size:20f32,
-            font:0usize,
-            text_colour:Black,
+            font_size,
+            font,
+            text_colour,
             align:Align::center()
         }
     }
@@ -0,0 +1,133 @@
+use crate::{
+    objects::SimpleObject,
+    theme::Theme,
+};
+
+use super::{
+    GeneralSettings,
+    TextView,
+    TextViewSettings,
+};
+
+use cat_engine::{
+    // types
+    Colour,
+    // structs
+    graphics::{Graphics,Graphics2D},
+    shapes::Rectangle,
+
+    glium::Surface,
+};
+
+/// Короткое сообщение поверх страницы, исчезающее через несколько секунд.
+pub struct Toast{
+    background:SimpleObject,
+    text:TextView,
+    background_colour:Colour,
+    text_colour:Colour,
+    /// Оставшееся время показа в секундах
+    remaining:f32,
+    fade:f32,
+}
+
+impl Toast{
+    pub fn new<S:Into<String>>(settings:ToastSettings<S>,graphics:&mut Graphics2D)->Toast{
+        let rect=settings.general.layout;
+
+        let background=Rectangle::new(rect,settings.background_colour);
+        let text_settings=TextViewSettings::styled(
+            settings.text,
+            settings.general,
+            settings.font,
+            settings.font_size,
+            settings.text_colour
+        );
+
+        Self{
+            background:SimpleObject::new(&background,graphics).unwrap(),
+            text:TextView::new(text_settings,graphics),
+            background_colour:settings.background_colour,
+            text_colour:settings.text_colour,
+            remaining:settings.duration,
+            fade:settings.fade,
+        }
+    }
+
+    /// Обновление времени показа и прозрачности.
+    /// Возвращает `false`, когда сообщение скрылось.
+    pub fn update(&mut self,elapsed:f32,graphics:&mut Graphics2D)->bool{
+        self.remaining-=elapsed;
+
+        // Плавное исчезновение в конце показа
+        let alpha=if self.fade>0f32{(self.remaining/self.fade).max(0f32).min(1f32)}else{1f32};
+        self.background.colour(graphics)[3]=self.background_colour[3]*alpha;
+        self.text.object().colour(graphics)[3]=self.text_colour[3]*alpha;
+
+        self.visible()
+    }
+
+    pub fn visible(&self)->bool{
+        self.remaining>0f32
+    }
+
+    pub fn draw<S:Surface>(&self,graphics:&mut Graphics<S>){
+        if self.visible(){
+            self.background.draw(graphics);
+            self.text.draw(graphics);
+        }
+    }
+}
+
+/// Настройки всплывающего сообщения
+pub struct ToastSettings<S:Into<String>>{
+    text:S,
+    general:GeneralSettings,
+    font:usize,
+    font_size:f32,
+    text_colour:Colour,
+    background_colour:Colour,
+    /// Время показа в секундах
+    duration:f32,
+    /// Время исчезновения в конце показа
+    fade:f32,
+}
+
+impl<S:Into<String>> ToastSettings<S>{
+    pub fn new(text:S,rect:[f32;4],theme:&Theme)->ToastSettings<S>{
+        Self{
+            text,
+            general:GeneralSettings::new(rect),
+            font:theme.font,
+            font_size:theme.font_size,
+            text_colour:theme.label_colour,
+            background_colour:[0f32,0f32,0f32,0.7f32],
+            duration:2.5f32,
+            fade:0.5f32,
+        }
+    }
+
+    pub fn font_size(mut self,size:f32)->ToastSettings<S>{
+        self.font_size=size;
+        self
+    }
+
+    pub fn text_colour(mut self,colour:Colour)->ToastSettings<S>{
+        self.text_colour=colour;
+        self
+    }
+
+    pub fn background_colour(mut self,colour:Colour)->ToastSettings<S>{
+        self.background_colour=colour;
+        self
+    }
+
+    pub fn duration(mut self,duration:f32)->ToastSettings<S>{
+        self.duration=duration;
+        self
+    }
+
+    pub fn fade(mut self,fade:f32)->ToastSettings<S>{
+        self.fade=fade;
+        self
+    }
+}
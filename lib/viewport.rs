@@ -0,0 +1,198 @@
+use cat_engine::glium::Rect;
+
+/// Точка привязки области.
+///
+/// Точка привязки области совмещается с той же точкой
+/// вставляемого прямоугольника.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum Anchor{
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor{
+    /// Доли ширины и высоты, задающие точку привязки.
+    pub fn factors(self)->[f32;2]{
+        match self{
+            Anchor::TopLeft=>[0f32,0f32],
+            Anchor::Top=>[0.5f32,0f32],
+            Anchor::TopRight=>[1f32,0f32],
+            Anchor::Left=>[0f32,0.5f32],
+            Anchor::Center=>[0.5f32,0.5f32],
+            Anchor::Right=>[1f32,0.5f32],
+            Anchor::BottomLeft=>[0f32,1f32],
+            Anchor::Bottom=>[0.5f32,1f32],
+            Anchor::BottomRight=>[1f32,1f32],
+        }
+    }
+
+    /// Прямоугольник размером `size`, привязанный к области `area`
+    /// со сдвигом `offset`.
+    pub fn place(self,area:[f32;4],offset:[f32;2],size:[f32;2])->[f32;4]{
+        let [fx,fy]=self.factors();
+        [
+            area[0]+(area[2]-size[0])*fx+offset[0],
+            area[1]+(area[3]-size[1])*fy+offset[1],
+            size[0],
+            size[1],
+        ]
+    }
+}
+
+/// Виртуальное разрешение.
+///
+/// Разметка страниц задаётся в координатах виртуального экрана
+/// (например, 1920×1080), которые переводятся в пиксели окна
+/// с сохранением пропорций. Оставшиеся по краям полосы закрашиваются.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct Viewport{
+    /// Виртуальный размер
+    design:[f32;2],
+    /// Размер окна в пикселях
+    surface:[f32;2],
+    scale:f32,
+    /// Сдвиг виртуального экрана в окне
+    offset:[f32;2],
+}
+
+impl Viewport{
+    pub const fn new(design:[f32;2])->Viewport{
+        Self{
+            design,
+            surface:design,
+            scale:1f32,
+            offset:[0f32;2],
+        }
+    }
+
+    /// Пересчёт под новый размер окна.
+    ///
+    /// Нулевой размер (свёрнутое окно) пропускается.
+    pub fn resize(&mut self,surface:[f32;2]){
+        if surface[0]<=0f32 || surface[1]<=0f32{
+            return
+        }
+
+        self.surface=surface;
+        self.scale=(surface[0]/self.design[0]).min(surface[1]/self.design[1]);
+        self.offset=[
+            (surface[0]-self.design[0]*self.scale)/2f32,
+            (surface[1]-self.design[1]*self.scale)/2f32,
+        ];
+    }
+
+    pub fn design(&self)->[f32;2]{
+        self.design
+    }
+
+    pub fn surface(&self)->[f32;2]{
+        self.surface
+    }
+
+    /// Множитель перевода виртуальных длин в пиксели.
+    pub fn scale(&self)->f32{
+        self.scale
+    }
+
+    /// Область виртуального экрана в пикселях окна [x,y,width,height].
+    pub fn area(&self)->[f32;4]{
+        [
+            self.offset[0],
+            self.offset[1],
+            self.design[0]*self.scale,
+            self.design[1]*self.scale,
+        ]
+    }
+
+    /// Перевод длины в пиксели.
+    pub fn length(&self,length:f32)->f32{
+        length*self.scale
+    }
+
+    /// Перевод размера в пиксели.
+    pub fn size(&self,[width,height]:[f32;2])->[f32;2]{
+        [width*self.scale,height*self.scale]
+    }
+
+    /// Перевод точки в пиксели окна.
+    pub fn point(&self,[x,y]:[f32;2])->[f32;2]{
+        [
+            self.offset[0]+x*self.scale,
+            self.offset[1]+y*self.scale,
+        ]
+    }
+
+    /// Перевод точки окна в виртуальные координаты.
+    pub fn design_point(&self,[x,y]:[f32;2])->[f32;2]{
+        [
+            (x-self.offset[0])/self.scale,
+            (y-self.offset[1])/self.scale,
+        ]
+    }
+
+    /// Перевод прямоугольника в пиксели окна.
+    pub fn rect(&self,[x,y,width,height]:[f32;4])->[f32;4]{
+        let [x,y]=self.point([x,y]);
+        [x,y,width*self.scale,height*self.scale]
+    }
+
+    /// Прямоугольник виртуального размера `size`, привязанный
+    /// к виртуальному экрану, в пикселях окна.
+    pub fn anchored(&self,anchor:Anchor,offset:[f32;2],size:[f32;2])->[f32;4]{
+        anchor.place(self.area(),self.size(offset),self.size(size))
+    }
+
+    /// Полосы по краям окна вне виртуального экрана [x,y,width,height].
+    pub fn bars(&self)->Vec<[f32;4]>{
+        let [x,y,width,height]=self.area();
+        let [surface_width,surface_height]=self.surface;
+
+        let mut bars=Vec::with_capacity(2);
+        if x>=0.5f32{
+            bars.push([0f32,0f32,x,surface_height]);
+            bars.push([x+width,0f32,surface_width-x-width,surface_height]);
+        }
+        if y>=0.5f32{
+            bars.push([0f32,0f32,surface_width,y]);
+            bars.push([0f32,y+height,surface_width,surface_height-y-height]);
+        }
+        bars
+    }
+}
+
+/// Прямоугольник окна в координатах OpenGL (отсчёт от нижнего левого угла)
+/// для области [x,y,width,height] окна высотой `height`.
+pub fn gl_rect(rect:[f32;4],height:f32)->Rect{
+    let [x,y,w,h]=rect;
+    Rect{
+        left:x.max(0f32).round() as u32,
+        bottom:(height-y-h).max(0f32).round() as u32,
+        width:w.max(0f32).round() as u32,
+        height:h.max(0f32).round() as u32,
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn ignores_minimised_window(){
+        let mut viewport=Viewport::new([1920f32,1080f32]);
+        viewport.resize([960f32,540f32]);
+        assert_eq!(viewport.scale(),0.5f32);
+
+        viewport.resize([0f32,0f32]);
+        viewport.resize([960f32,0f32]);
+        assert_eq!(viewport.scale(),0.5f32);
+        assert_eq!(viewport.surface(),[960f32,540f32]);
+        assert_eq!(viewport.design_point([480f32,270f32]),[960f32,540f32]);
+    }
+}
@@ -1,8 +1,18 @@
+use crate::resources;
+
+use lib::{
+    log,
+    error::{GameError,GameResult},
+};
+
 use std::{
-    io::{Read,Write},
-    fs::OpenOptions,
+    io::{self,Read,Write,ErrorKind},
+    fs::{OpenOptions,create_dir_all},
 };
 
+// Путь к файлу настроек относительно папки пользователя
+const settings_path:&'static str="settings/game_settings";
+
 pub struct GameSettings{
     pub continue_game:bool, // Флаг продолжения игры
     pub user_name:String,
@@ -11,9 +21,12 @@ pub struct GameSettings{
     pub pages:usize, // Количество страниц в игре
     pub signs_per_frame:f32, // Знаков на кадр
     pub volume:f32, // Громкость игры, 0 - 128
-    pub screenshot:u32, // номер следующего скришота
 
     pub monitor:usize, // Номер монитора в списке мониторов
+    pub language:String, // Код языка, пустой - язык по умолчанию
+    pub fullscreen:bool, // Полноэкранный режим или окно
+    pub parallax:bool, // Сдвиг слоёв страниц за курсором
+    pub system_cursor:bool, // Системный курсор вместо курсора игры
 }
 
 impl GameSettings{
@@ -27,55 +40,88 @@ impl GameSettings{
             saved_dialogue:0,
             signs_per_frame:0.25f32,
             volume:1f32,
-            screenshot:0u32,
 
             monitor:0usize,
+            language:String::new(),
+            fullscreen:true,
+            parallax:true,
+            system_cursor:false,
         }
     }
 
-    /// Загрузка настроек
-    pub fn load()->GameSettings{
+    /// Загрузка настроек.
+    ///
+    /// Если файла ещё нет, возвращаются настройки по умолчанию.
+    pub fn load()->GameResult<GameSettings>{
+        let path=unsafe{resources.user_path(settings_path)};
+        if !path.exists(){
+            log::info("файла настроек нет - настройки по умолчанию");
+            return Ok(GameSettings::new())
+        }
+
+        let settings_file=OpenOptions::new().read(true).open(&path).map_err(|e|GameError::file(&path,e))?;
+        let settings=GameSettings::read(settings_file).map_err(|e|GameError::file(&path,e))?;
+
+        log::info(format!("настройки загружены: страница {}, диалог {}",settings.saved_page,settings.saved_dialogue));
+
+        Ok(settings)
+    }
+
+    fn read<R:Read>(mut settings_file:R)->io::Result<GameSettings>{
         let mut settings=GameSettings::new();
 
-        // Общие настройки пользоавателя
-        let mut settings_file=OpenOptions::new().read(true).open("settings/game_settings").unwrap();
         let mut buffer=[0u8;8];
 
         // Продолжение игры
-        settings_file.read_exact(&mut buffer[0..1]).unwrap();
+        settings_file.read_exact(&mut buffer[0..1])?;
         if buffer[0]!=0{
             settings.continue_game=true;
             // Имя пользователя при продолжении игры
-            settings_file.read_exact(&mut buffer[0..1]).unwrap();
-            
+            settings_file.read_exact(&mut buffer[0..1])?;
+
             let mut name=vec![0u8;buffer[0] as usize];
-            settings_file.read_exact(&mut name).unwrap();
-            settings.user_name=String::from_utf8(name).unwrap();
+            settings_file.read_exact(&mut name)?;
+            settings.user_name=String::from_utf8(name).map_err(|e|io::Error::new(ErrorKind::InvalidData,e))?;
         }
         // Текущая страница игры
-        settings_file.read_exact(&mut buffer).unwrap();
+        settings_file.read_exact(&mut buffer)?;
         settings.saved_page=usize::from_be_bytes(buffer);
         // Текущее положение в диалоге на странице
-        settings_file.read_exact(&mut buffer).unwrap();
+        settings_file.read_exact(&mut buffer)?;
         settings.saved_dialogue=usize::from_be_bytes(buffer);
         //
         let mut buffer=[0u8;4];
         // Количество символов в секунду
-        settings_file.read_exact(&mut buffer).unwrap();
+        settings_file.read_exact(&mut buffer)?;
         settings.signs_per_frame=f32::from_be_bytes(buffer);
         // Значение громкости
-        settings_file.read_exact(&mut buffer).unwrap();
+        settings_file.read_exact(&mut buffer)?;
         settings.volume=f32::from_be_bytes(buffer);
-        // Количество сделанных скриншотов (номер следующего)
-        settings_file.read_exact(&mut buffer).unwrap();
-        settings.screenshot=u32::from_be_bytes(buffer);
 
         // Выбранный монитор
         let mut buffer=[0u8;8];
-        settings_file.read_exact(&mut buffer).unwrap();
+        settings_file.read_exact(&mut buffer)?;
         settings.monitor=usize::from_be_bytes(buffer);
 
-        settings
+        // Язык
+        settings_file.read_exact(&mut buffer[0..1])?;
+        let mut language=vec![0u8;buffer[0] as usize];
+        settings_file.read_exact(&mut language)?;
+        settings.language=String::from_utf8(language).map_err(|e|io::Error::new(ErrorKind::InvalidData,e))?;
+
+        // Полноэкранный режим
+        settings_file.read_exact(&mut buffer[0..1])?;
+        settings.fullscreen=buffer[0]!=0;
+
+        // Параллакс
+        settings_file.read_exact(&mut buffer[0..1])?;
+        settings.parallax=buffer[0]!=0;
+
+        // Системный курсор
+        settings_file.read_exact(&mut buffer[0..1])?;
+        settings.system_cursor=buffer[0]!=0;
+
+        Ok(settings)
     }
 
     /// Установка позиций для сохранения
@@ -85,37 +131,94 @@ impl GameSettings{
     }
 
     /// Сохрание настроек
-    pub fn save(&mut self){
-        let mut settings_file=OpenOptions::new().write(true).truncate(true).open("settings/game_settings").unwrap();
+    pub fn save(&self)->GameResult<()>{
+        let path=unsafe{resources.user_path(settings_path)};
+        if let Some(directory)=path.parent(){
+            create_dir_all(directory).map_err(|e|GameError::file(directory,e))?;
+        }
+
+        let settings_file=OpenOptions::new().write(true).create(true).truncate(true).open(&path)
+                .map_err(|e|GameError::file(&path,e))?;
+        self.write(settings_file).map_err(|e|GameError::file(&path,e))?;
+
+        log::info(format!("настройки сохранены: страница {}, диалог {}",self.saved_page,self.saved_dialogue));
+
+        Ok(())
+    }
+
+    fn write<W:Write>(&self,mut settings_file:W)->io::Result<()>{
         if self.continue_game{
-            settings_file.write_all(&[1]).unwrap(); // Продолжение игры
+            settings_file.write_all(&[1])?; // Продолжение игры
             // Имя пользователя при продолжении игры
             let buffer=self.user_name.as_bytes();
             let len=buffer.len() as u8;
-            settings_file.write_all(&[len]).unwrap();
-            settings_file.write_all(buffer).unwrap();
+            settings_file.write_all(&[len])?;
+            settings_file.write_all(buffer)?;
         }
         else{
-            settings_file.write_all(&[0]).unwrap(); // Новая игра
+            settings_file.write_all(&[0])?; // Новая игра
         }
         // Текущая страница игры
         let mut buffer=self.saved_page.to_be_bytes();
-        settings_file.write_all(&buffer).unwrap();
+        settings_file.write_all(&buffer)?;
         // Текущее положение в диалоге на странице
         buffer=self.saved_dialogue.to_be_bytes();
-        settings_file.write_all(&buffer).unwrap();
+        settings_file.write_all(&buffer)?;
         // Количество символов в секунду
         let mut buffer=self.signs_per_frame.to_be_bytes();
-        settings_file.write_all(&buffer).unwrap();
+        settings_file.write_all(&buffer)?;
         // Значение громкости
         buffer=self.volume.to_be_bytes();
-        settings_file.write_all(&buffer).unwrap();
-        // Количество сделанных скриншотов (номер следующего)
-        buffer=self.screenshot.to_be_bytes();
-        settings_file.write_all(&buffer).unwrap();
+        settings_file.write_all(&buffer)?;
 
         // Выбранный монитор
         let buffer=self.monitor.to_be_bytes();
-        settings_file.write_all(&buffer).unwrap();
+        settings_file.write_all(&buffer)?;
+
+        // Язык
+        let buffer=self.language.as_bytes();
+        settings_file.write_all(&[buffer.len() as u8])?;
+        settings_file.write_all(buffer)?;
+
+        // Полноэкранный режим
+        settings_file.write_all(&[self.fullscreen as u8])?;
+
+        // Параллакс
+        settings_file.write_all(&[self.parallax as u8])?;
+
+        // Системный курсор
+        settings_file.write_all(&[self.system_cursor as u8])?;
+
+        settings_file.flush()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn settings_round_trip(){
+        let mut settings=GameSettings::new();
+        settings.continue_game=true;
+        settings.user_name="Алиса".to_string();
+        settings.set_saved_position(3usize,14usize);
+        settings.language="et".to_string();
+        settings.fullscreen=false;
+        settings.parallax=false;
+        settings.system_cursor=true;
+
+        let mut buffer=Vec::new();
+        settings.write(&mut buffer).unwrap();
+        let loaded=GameSettings::read(buffer.as_slice()).unwrap();
+
+        assert!(loaded.continue_game);
+        assert_eq!(loaded.user_name,"Алиса");
+        assert_eq!((loaded.saved_page,loaded.saved_dialogue),(3usize,14usize));
+        assert_eq!(loaded.language,"et");
+        assert!(!loaded.fullscreen && !loaded.parallax && loaded.system_cursor);
+
+        // Обрезанный файл - ошибка, а не паника
+        assert!(GameSettings::read(&buffer[..buffer.len()-1]).is_err());
+    }
+}
@@ -6,14 +6,39 @@ use game_settings::GameSettings;
 
 mod pages;
 use pages::{
-    SwipeDirection,
     LoadingScreen,
-    MainMenu
+    MainMenu,
+    ErrorPage,
+    page_or_error,
+    loading_screen_frames,
+};
+
+use lib::scene::{
+    Scene,
+    SceneAction,
+    SceneStack,
 };
 
 use lib::{
     *,
     colours::*,
+    error::{GameError,GameResult},
+    log::{self,Level,LogSettings},
+    manifest::ResourceManifest,
+    page_table::PageTable,
+    assets::{AssetCache,AssetCacheSettings,load_image},
+    atlas::{Atlas,AtlasBuilder},
+    cursor::{Cursor,CursorSettings,CursorState},
+    resources::ResourceResolver,
+    localization::Localization,
+    theme::Theme,
+    clock::Clock,
+    parallax::{Parallax,ParallaxLayer,ParallaxSettings},
+    screenshot::ScreenshotSaver,
+    user_interface::{Toast,ToastSettings},
+    viewport::{Anchor,Viewport,gl_rect},
+    objects::{TextureObject,TexturedObject},
+    transition::TransitionObjects,
 };
 
 use cat_engine::{
@@ -59,8 +84,10 @@ use cat_engine::{
         BlendingFunction,
         LinearBlendingFactor,
         glutin::window::Icon,
-        glutin::dpi::Size,
+        glutin::dpi::{Size,PhysicalSize},
+        glutin::window::Fullscreen,
         DrawError,
+        Surface,
         framebuffer::SimpleFrameBuffer,
     },
     texture::{
@@ -84,67 +111,164 @@ use std::{
     path::{PathBuf,Path},
 };
 
-pub enum Game{
-    MainMenu,
-    Pause,
-    Next,
-    Exit,
-}
+/// Продолжительность перехода между страницами в секундах
+const swipe_duration:f32=0.46f32;
+
+pub const game_name:&'static str="A Visual Novel by Clomance";
 
-// Индексы главных текстурных объектов
-const mouse_cursor_icon_index:usize=0;
+// Глубины слоёв параллакса: сдвиг слоя - отклонение курсора
+// от центра окна, делённое на глубину
+const wallpaper_depth:f32=16f32;
+const character_depth:f32=14f32;
+const particle_depth:f32=12f32;
+const interface_depth:f32=10f32;
 
-const wallpaper_index:usize=1;
+/// Жёсткость пружины сглаживания параллакса в радианах в секунду
+const parallax_stiffness:f32=10f32;
 
-/// Картинка для переходов.
-const swipe_screen_index:usize=2;
-const swipe_updates:u8=23;
+// Слои параллакса в порядке добавления в `parallax_settings`
+pub const wallpaper_layer:ParallaxLayer=ParallaxLayer(0usize);
+pub const character_layer:ParallaxLayer=ParallaxLayer(1usize);
+pub const particle_layer:ParallaxLayer=ParallaxLayer(2usize);
+pub const interface_layer:ParallaxLayer=ParallaxLayer(3usize);
 
-// Пути ресурсов
-const cursor_icon:&'static str="./resources/images/mouse_icon.png";
+/// Путь к оформлению интерфейса относительно папки ресурсов
+const theme_path:&'static str="theme";
 
-const audio_tracks_paths:&[&'static str]=&[
-    "./resources/audio/audio.mp3",
-    "./resources/audio/button_pressed.mp3",
-    "./resources/audio/screenshot.mp3",
-];
+/// Путь к таблице страниц относительно папки ресурсов
+const page_table_path:&'static str="page_table.txt";
 
-const fonts_paths:&[&'static str]=&[
-    "./resources/fonts/main.font",
-    "./resources/fonts/dialogue.font",
-];
+/// Предел памяти декодированных обоев и спрайтов
+const asset_memory_limit:usize=256usize*1024usize*1024usize;
 
-const main_menu_wallpaper_path:&'static str="./resources/images/wallpapers/main_menu_wallpaper.png";
+/// Папка кэша обоев с изменённым размером относительно папки пользователя
+const wallpaper_cache_directory:&'static str="settings/wallpapers";
 
-const decoration_image_paths:&[&'static str]=&[
-    "resources/images/rose.png",
-];
+/// Наибольший размер страницы атласа интерфейса
+const ui_atlas_size:[u32;2]=[2048u32;2];
 
-// Названия для аудио треков
-const audio_tracks_names:&[&'static str]=&[
-    "main_theme",
-    "button_pressed",
-    "screenshot"
-];
+/// Пустое место вокруг изображений атласа в пикселях
+const ui_atlas_padding:u32=2u32;
 
+/// Обязательные изображения из манифеста в атласе интерфейса
+/// (кадры экрана загрузки добавляются из `loading_screen`,
+/// изображения состояний курсора - если они есть)
+const ui_atlas_images:[&'static str;2]=["cursor","leaf"];
 
-// Алфавит для рендеринга текста (остальные символы будут выведены как неопределённые)
-const alphabet:&'static str="АаБбВвГгДдЕеЁёЖжЗзИиЙйКкЛлМмНнОоПпРрСсТтУуФфХхЦцЧчШшЩщЪъЫыЬьЭэЮюЯя1234567890AaBbCcDdEeFfGgHhIiJjKkLlMmNnOoPpQqRrSsTtUuVvWwXxYyZz:();[]!.,";
+/// Папка журнала и отчётов о падении относительно папки пользователя
+const log_directory:&'static str="settings";
 
-pub const game_name:&'static str="A Visual Novel by Clomance";
+/// Папка снимков экрана относительно папки пользователя
+pub const screenshot_directory:&'static str="screenshots";
 
-pub const wallpaper_movement_scale:f32=16f32;
+/// Имя журнала ошибок в папке снимков экрана
+const error_log_name:&'static str="errors.log";
+
+/// Размер всплывающего сообщения и его отступ от нижнего края виртуального экрана
+const toast_size:[f32;2]=[900f32,70f32];
+const toast_offset:[f32;2]=[0f32,-60f32];
+
+/// Виртуальное разрешение для разметки страниц
+const design_resolution:[f32;2]=[1920f32,1080f32];
+
+/// Доля экрана, занимаемая окном в оконном режиме
+const windowed_scale:f32=2f32/3f32;
 
 pub static mut game_settings:GameSettings=GameSettings::new();
 
+/// Поиск ресурсов и пользовательских файлов.
+pub static mut resources:ResourceResolver=ResourceResolver::new();
+
+/// Строки интерфейса на выбранном языке.
+pub static mut localization:Localization=Localization::new();
+
+/// Перевод виртуальной разметки в пиксели окна.
+pub static mut viewport:Viewport=Viewport::new(design_resolution);
+
+/// Оформление интерфейса, из которого страницы берут настройки виджетов.
+pub static mut theme:Theme=Theme::new();
+
 fn main(){
-    // unsafe{
-    //     game_settings=GameSettings::load();
-    // }
+    // Папка ресурсов и моды из аргументов командной строки
+    unsafe{
+        resources=ResourceResolver::from_args(std::env::args().skip(1));
+    }
+
+    // Журнал и отчёт о падении
+    unsafe{
+        log::init(LogSettings::new(resources.user_path(log_directory)));
+        // Ошибки отдельно - рядом со снимками экрана
+        log::add_sink(
+            LogSettings::new(resources.user_path(screenshot_directory))
+                .name(error_log_name)
+                .level(Level::Error)
+        );
+    }
+    log::install_panic_hook();
+    log::info(format!("запуск, ресурсы: {}",unsafe{resources.root().display()}));
+
+    // Настройки пользователя (при ошибке - настройки по умолчанию)
+    match GameSettings::load(){
+        Ok(loaded)=>unsafe{game_settings=loaded},
+        Err(error)=>log_error(&error),
+    }
+
+    // Первая ошибка до создания окна - показывается вместо экрана загрузки,
+    // а игра запускается с настройками по умолчанию
+    let mut startup_error:Option<GameError>=None;
+
+    // Загрузка описания ресурсов
+    let manifest=match ResourceManifest::load(unsafe{&resources}){
+        Ok(manifest)=>manifest,
+        Err(error)=>{
+            startup_error.get_or_insert(error);
+            ResourceManifest::new()
+        }
+    };
+
+    // Загрузка строк интерфейса
+    if startup_error.is_none(){
+        unsafe{
+            match Localization::load(manifest.languages.clone(),&game_settings.language){
+                Ok(loaded)=>{
+                    localization=loaded;
+                    game_settings.language=localization.language().to_string();
+                }
+                Err(error)=>startup_error=Some(error),
+            }
+        }
+    }
+
+    // Загрузка таблицы страниц
+    let page_table=match PageTable::load(unsafe{resources.resolve(page_table_path)}){
+        Ok(page_table)=>page_table,
+        Err(error)=>{
+            startup_error.get_or_insert(error);
+            PageTable::new()
+        }
+    };
+
+    // Загрузка оформления интерфейса (может быть заменено модом)
+    unsafe{
+        match Theme::load(resources.resolve(theme_path)){
+            Ok(loaded)=>theme=loaded,
+            Err(error)=>{
+                startup_error.get_or_insert(error);
+            }
+        }
+    }
+
+    // Подключение аудио системы (без звука игра продолжается)
+    let audio=match Audio::default(AudioSettings::new()){
+        Ok(audio)=>Some(AudioWrapper::new(audio)),
+        Err(error)=>{
+            log_error(&GameError::system(error));
+            None
+        }
+    };
 
-    // Подключение аудио системы
-    let audio=Audio::default(AudioSettings::new()).unwrap();
-    let mut audio=AudioWrapper::new(audio);
+    // Размер экрана
+    let mut screen=design_resolution;
 
     // Настройка и создание окна и загрузка функций OpenGL
     let (mut window,mut graphics)=match Window::new(|mut monitors,window_settings|{
@@ -160,24 +284,36 @@ fn main(){
 
         // Размер монитора
         let size=monitor.size();
+        screen=[size.width as f32,size.height as f32];
 
-        let fullscreen=cat_engine::glium::glutin::window::Fullscreen::Borderless(Some(monitor));
+        let fullscreen=unsafe{game_settings.fullscreen};
 
-        let icon=load_window_icon();
+        let icon=manifest.require_image("window_icon").and_then(load_window_icon);
+        let icon=match icon{
+            Ok(icon)=>Some(icon),
+            Err(error)=>{
+                startup_error.get_or_insert(error);
+                None
+            }
+        };
 
         window_settings.general.initial_colour=Some(White);
 
         window_settings.general.updates_per_second=50;
 
-        // Установка размера окна (требуется на некоторых версиях Linux)
-        window_settings.window_attributes.inner_size=Some(Size::Physical(size));
+        if fullscreen{
+            // Установка размера окна (требуется на некоторых версиях Linux)
+            window_settings.window_attributes.inner_size=Some(Size::Physical(size));
+            window_settings.window_attributes.fullscreen=Some(Fullscreen::Borderless(Some(monitor)));
+        }
+        else{
+            window_settings.window_attributes.inner_size=Some(windowed_size(screen));
+        }
 
         window_settings.window_attributes.title=game_name.to_string();
-        window_settings.window_attributes.fullscreen=Some(fullscreen);
-        window_settings.window_attributes.resizable=false;
-        window_settings.window_attributes.decorations=false;
-        window_settings.window_attributes.always_on_top=true;
-        window_settings.window_attributes.window_icon=Some(icon);
+        window_settings.window_attributes.resizable=true;
+        window_settings.window_attributes.decorations=!fullscreen;
+        window_settings.window_attributes.window_icon=icon;
 
         window_settings.vsync=true;
         window_settings.debug=false;
@@ -186,37 +322,56 @@ fn main(){
         window_settings.pixel_fmt_req.hardware_accelerated=None;
 
 
-        window_settings.graphics_base_settings.texture.vertex_buffer_size=40usize;
+        // Фон из изображения занимает девять текстурных объектов
+        window_settings.graphics_base_settings.texture.vertex_buffer_size=400usize;
         window_settings.graphics_base_settings.texture.vertex_buffer_offset=0usize;
-        window_settings.graphics_base_settings.texture.object_buffer_size=10usize;
+        window_settings.graphics_base_settings.texture.object_buffer_size=100usize;
 
 
-        window_settings.graphics_base_settings.simple.vertex_buffer_size=100usize;
+        // Фон с рамкой занимает два простых объекта
+        window_settings.graphics_base_settings.simple.vertex_buffer_size=200usize;
         window_settings.graphics_base_settings.simple.vertex_buffer_offset=0usize;
-        window_settings.graphics_base_settings.simple.object_buffer_size=10usize;
+        window_settings.graphics_base_settings.simple.object_buffer_size=50usize;
 
         window_settings.graphics_base_settings.text.glyph_texture_size=[512u32;2];
     }){
         Ok(window)=>window,
-        #[cfg(debug_assertions)]
         Err(e)=>{
-            #[cfg(debug_assertions)]
-            println!("{:?}",e);
+            log_error(&GameError::system(format!("{:?}",e)));
             return
         }
-        #[cfg(not(debug_assertions))]
-        Err(_)=>return,
     };
 
-    // Установка видимости курсора
-    window.display().gl_window().window().set_cursor_visible(false);
+    unsafe{
+        viewport.resize([window_width,window_height]);
+    }
 
-    let mut image_base=ImageObject::new(unsafe{[
-            window_center[0]-15f32,
-            window_center[1]-15f32,
-            30f32,
-            30f32
-        ]},
+    // Фоны виджетов из оформления
+    unsafe{
+        if let Err(error)=theme.load_styles(&manifest,window.display(),&mut graphics){
+            startup_error.get_or_insert(error);
+        }
+    }
+
+    // Сборка атласа интерфейса
+    let atlas=load_ui_atlas(&manifest).and_then(|mut atlas|{
+        let textures=atlas.upload(window.display(),&mut graphics)?;
+        Ok((atlas,textures))
+    });
+    let (atlas,atlas_textures)=match atlas{
+        Ok(atlas)=>atlas,
+        Err(error)=>{
+            startup_error.get_or_insert(error);
+            (Atlas::new(),Vec::new())
+        }
+    };
+
+    // Курсор мыши из атласа
+    // (если его нет или так выбрано в настройках - системный курсор)
+    let mut cursor=Cursor::new(cursor_settings(),&atlas,&atlas_textures,&mut graphics);
+    cursor.set_system(unsafe{game_settings.system_cursor},&window);
+
+    let mut image_base=ImageObject::new(wallpaper_rect(),
         [
             0f32,
             0f32,
@@ -225,77 +380,256 @@ fn main(){
         ],
         White
     );
-    { // Загрузка иконки курсора мыши
-        
-        let mouse_texture=Texture::from_path(cursor_icon,window.display()).unwrap();
-        let mouse_texture_index=graphics.add_texture(mouse_texture);
-        let _mouse_cursor_icon=graphics.add_textured_object(&image_base,mouse_texture_index).unwrap();
-    }
-    { // Создание текстуры чуть больше размера экрана
-        // Создание тектуры для обоев
-        // Размеры для обоев
-        let (dx,dy,width,height)=unsafe{
-            let dx=window_width/(wallpaper_movement_scale*2f32);
-            let dy=window_height/(wallpaper_movement_scale*2f32);
-            let width=(window_width+2f32*dx).ceil();
-            let height=(window_height+2f32*dy).ceil();
-
-            (dx,dy,width,height)
-        };
 
-        image_base.set_rect([-dx,-dy,width,height]);
+    let (wallpaper_texture,wallpaper)={ // Создание текстуры чуть больше размера экрана
 
-        let wallpaper_texture=Texture::empty([width as u32,height as u32],window.display()).unwrap();
-        let wallpaper_texture_index=graphics.add_texture(wallpaper_texture);
-        let _wallpaper=graphics.add_textured_object(&image_base,wallpaper_texture_index).unwrap();
-    }
+        let wallpaper_texture=Texture::empty(wallpaper_size(screen),window.display()).unwrap();
+        let wallpaper_texture=TextureObject::new(wallpaper_texture,&mut graphics);
+        let wallpaper=match screen_object(&image_base,&wallpaper_texture,&mut graphics){
+            Ok(wallpaper)=>wallpaper,
+            Err(error)=>{
+                log_error(&error);
+                return
+            }
+        };
+
+        (wallpaper_texture,wallpaper)
+    };
 
     image_base.set_rect(unsafe{[0f32,0f32,window_width,window_height]});
-    {
-        let swipe_screen_texture=Texture::empty(
-            unsafe{[window_width as u32,window_height as u32]},window.display()
-        ).unwrap();
-        let swipe_screen_texture_index=graphics.add_texture(swipe_screen_texture);
-        let _swipe_screen_index=graphics.add_textured_object(&image_base,swipe_screen_texture_index).unwrap();
+    // Картинка для переходов (размером с экран, чтобы хватало при смене режима окна)
+    let swipe_screen_size=[screen[0] as u32,screen[1] as u32];
+    let swipe_screen_texture=Texture::empty(swipe_screen_size,window.display()).unwrap();
+    let swipe_screen_texture=TextureObject::new(swipe_screen_texture,&mut graphics);
+    let swipe_screen=match screen_object(&image_base,&swipe_screen_texture,&mut graphics){
+        Ok(swipe_screen)=>swipe_screen,
+        Err(error)=>{
+            log_error(&error);
+            return
+        }
+    };
+    let swipe_screen_target=Texture::empty(swipe_screen_size,window.display()).unwrap();
+    let transition=TransitionObjects::new(
+        swipe_screen,
+        swipe_screen_target,
+        unsafe{[window_width,window_height]},
+        screen,
+        &mut graphics
+    );
+
+    let asset_settings=AssetCacheSettings::new()
+            .memory_limit(asset_memory_limit)
+            .scaled_cache(unsafe{resources.user_path(wallpaper_cache_directory)});
+    let assets=AssetCache::new(manifest.clone(),asset_settings);
+
+    let mut parallax=Parallax::new(parallax_settings());
+    parallax.set_enabled(unsafe{game_settings.parallax});
+
+    let mut context=GameContext{
+        audio,
+        manifest,
+        page_table,
+        assets,
+        data:LoadingMainData::new(),
+        parallax,
+        atlas,
+        atlas_textures,
+        cursor,
+        screenshots:ScreenshotSaver::new(unsafe{resources.user_path(screenshot_directory)}),
+        toast:None,
+        fonts_loaded:false,
+        wallpaper,
+        transition,
+        screen,
+        textures:vec![wallpaper_texture,swipe_screen_texture],
+    };
+
+    let mut scenes=SceneStack::new(|context:&GameContext|&context.transition);
+
+    // Создание страницы загрузки или страницы ошибки запуска
+    scenes.handle(
+        SceneAction::push(move|context:&mut GameContext,window:&mut Window,graphics:&mut Graphics2D|{
+            match startup_error{
+                Some(error)=>Box::new(ErrorPage::new(window,graphics,context,&error)) as Box<dyn Scene<GameContext>>,
+                None=>page_or_error(LoadingScreen::new(window,graphics,context),context,window,graphics),
+            }
+        }),
+        &mut context,
+        &mut window,
+        &mut graphics
+    );
+
+    let mut clock=Clock::new();
+
+    // Цикл игры - события передаются верхней сцене
+    window.run(|window,event|{
+        match event{
+            WindowEvent::CloseRequested=>scenes.clear(&mut context,window,&mut graphics),
+
+            WindowEvent::Update=>{
+                let elapsed=clock.tick();
+                context.parallax.update(elapsed,unsafe{mouse_cursor.center_radius()});
+                context.update_overlay(elapsed,&mut graphics);
+                scenes.update(elapsed,&mut context,window,&mut graphics)
+            }
+
+            WindowEvent::RedrawRequested=>{
+                window.draw(&graphics,|graphics|{
+                    scenes.draw(&context,graphics);
+
+                    // Полосы вокруг виртуального экрана
+                    unsafe{
+                        for bar in viewport.bars(){
+                            let rect=gl_rect(bar,window_height);
+                            graphics.frame.clear(Some(&rect),Some((0f32,0f32,0f32,1f32)),false,None,None);
+                        }
+                    }
+
+                    // Сообщение и курсор поверх всех сцен
+                    if let Some(toast)=&context.toast{
+                        toast.draw(graphics);
+                    }
+                    context.cursor.draw(graphics);
+                }).unwrap();
+            }
+
+            // При сворачивании окна приходит нулевой размер -
+            // страницы не перестраиваются
+            WindowEvent::Resized([width,height])=>if width!=0 && height!=0{
+                unsafe{
+                    viewport.resize([window_width,window_height]);
+                }
+                context.resize(&mut graphics);
+                scenes.resize(&mut context,window,&mut graphics);
+            }
+
+            WindowEvent::KeyboardPressed(KeyboardButton::F5)=>make_screenshot(window,&context),
+
+            event=>scenes.input(event,&mut context,window,&mut graphics),
+        }
+
+        if scenes.is_empty(){
+            log::info("выход");
+            window.stop_events();
+        }
+    });
+}
+
+/// Общие данные для всех сцен.
+pub struct GameContext{
+    /// Нет, если не удалось подключить аудио систему
+    pub audio:Option<AudioWrapper>,
+    pub manifest:ResourceManifest,
+    pub page_table:PageTable,
+    /// Обои и спрайты, декодируемые по запросу
+    pub assets:AssetCache,
+    /// Данные начальной загрузки
+    pub data:LoadingMainData,
+    /// Сдвиг слоёв страниц за курсором
+    pub parallax:Parallax,
+    /// Мелкие изображения интерфейса и кадры анимаций
+    pub atlas:Atlas,
+    /// Текстуры страниц атласа
+    atlas_textures:Vec<TextureObject>,
+    /// Иконка курсора мыши
+    pub cursor:Cursor,
+    /// Сохранение снимков экрана в отдельном потоке
+    pub screenshots:ScreenshotSaver,
+    /// Всплывающее сообщение поверх страниц
+    pub toast:Option<Toast>,
+    /// Шрифты загружены - можно показывать сообщения
+    pub fonts_loaded:bool,
+    /// Обои (чуть больше экрана)
+    pub wallpaper:TexturedObject,
+    /// Объекты для переходов между страницами
+    pub transition:TransitionObjects,
+    /// Размер экрана (наибольший размер окна)
+    pub screen:[f32;2],
+    /// Текстуры главных объектов
+    textures:Vec<TextureObject>,
+}
+
+impl GameContext{
+    /// Запуск трека (`repeats` - 0 для бесконечного повтора).
+    pub fn play_track(&self,name:&str,repeats:u32){
+        if let Some(audio)=&self.audio{
+            if let Err(e)=audio.play_track(name,repeats){
+                log::warning(format!("трек '{}' не запущен: {}",name,e));
+            }
+        }
     }
 
-    // Данные для начальной загрузки
-    let mut main_data=LoadingMainData::new();
+    /// Положение курсора в координатах несдвинутого слоя параллакса.
+    pub fn cursor_in_layer(&self,layer:ParallaxLayer)->[f32;2]{
+        self.parallax.to_layer(layer,unsafe{mouse_cursor.position()})
+    }
 
-    // Создание и запуск страницы загрузки
-    if let Game::Exit=LoadingScreen::new(&window,&mut graphics).run(&mut window,&mut graphics,&audio,&mut main_data){
-        return
+    /// Текстура страницы атласа и текстурные координаты изображения `name`.
+    pub fn atlas_image(&self,name:&str)->GameResult<(&TextureObject,[f32;4])>{
+        let region=self.atlas.require(name)?;
+        Ok((&self.atlas_textures[region.page],region.uv))
     }
 
-    // Загрузка треков в хранилище
-    for (track,name) in main_data.audio.into_iter().zip(audio_tracks_names.iter()){
-        audio.push_track(track,name.to_string());
+    /// Показ сообщения внизу экрана (до загрузки шрифтов - только в журнал).
+    pub fn show_toast<S:Into<String>>(&mut self,text:S,graphics:&mut Graphics2D){
+        let text=text.into();
+        if !self.fonts_loaded{
+            log::info(text);
+            return
+        }
+
+        let settings=unsafe{
+            let rect=viewport.anchored(Anchor::Bottom,toast_offset,toast_size);
+            ToastSettings::new(text,rect,&theme).font_size(viewport.length(theme.font_size))
+        };
+        self.toast=Some(Toast::new(settings,graphics));
     }
 
-    // Запуск мелодии главной темы (повторять бесконечно)
-    audio.play_track("main_theme",0u32);
-
-    let images=main_data.textures;
-
-    // Цикл игры
-    'game:loop{
-        // Главное меню
-        match{
-            let mut menu=MainMenu::new(&window,&mut graphics,&images[0..2]);
-            menu.open(&mut window,SwipeDirection::Left,&mut graphics);
-            menu.run(&mut window,&mut graphics,&mut audio)
-        }{
-            Game::Exit=>break 'game,
-            _=>{}
+    /// Результаты сохранения снимков и время показа сообщения.
+    fn update_overlay(&mut self,elapsed:f32,graphics:&mut Graphics2D){
+        while let Some(result)=self.screenshots.poll(){
+            let message=match result{
+                Ok(path)=>{
+                    log::info(format!("снимок экрана {}",path.display()));
+                    let name=path.file_name().map_or(String::new(),|name|name.to_string_lossy().into_owned());
+                    format!("{}: {}",unsafe{localization.get("screenshot_saved")},name)
+                }
+                Err(error)=>{
+                    log_error(&error);
+                    unsafe{localization.get("screenshot_failed")}
+                }
+            };
+            self.show_toast(message,graphics);
+        }
+
+        if let Some(toast)=&mut self.toast{
+            if !toast.update(elapsed,graphics){
+                self.toast=None;
+            }
         }
     }
+
+    /// Перестроение главных объектов под новый размер окна.
+    fn resize(&mut self,graphics:&mut Graphics2D){
+        // Сообщение не перестраивается - оно скоро скроется
+        self.toast=None;
+        self.cursor.resize(unsafe{viewport.length(theme.cursor_size)},graphics);
+
+        let image_base=ImageObject::new(wallpaper_rect(),[0f32,0f32,1f32,1f32],White);
+        self.wallpaper.rewrite_vertices(&image_base,graphics);
+
+        self.transition.resize(unsafe{[window_width,window_height]},graphics);
+    }
 }
 
+pub type GameSceneAction=SceneAction<GameContext>;
+
 /// Данные при начальной загрузке.
 pub struct LoadingMainData{
     pub fonts:Option<Vec<FontOwner>>,
     pub audio:Vec<ChanneledTrack>,
-    pub textures:Vec<RgbaImage>,
+    /// Символы, используемые в игре, для кэширования шрифтов
+    pub characters:Vec<char>,
 }
 
 impl LoadingMainData{
@@ -303,66 +637,139 @@ impl LoadingMainData{
         Self{
             fonts:None,
             audio:Vec::new(),
-            textures:Vec::new(),
+            characters:Vec::new(),
         }
     }
 }
 
-/// Загрузка иконки окна
-fn load_window_icon()->Icon{
-    let image=cat_engine::image::open("./resources/images/window_icon.png").unwrap();
-    let vec=image.to_bytes();
-    let (width,height)=image.dimensions();
+fn parallax_settings()->ParallaxSettings{
+    ParallaxSettings::new()
+        .stiffness(parallax_stiffness)
+        .layer(wallpaper_depth)
+        .layer(character_depth)
+        .layer(particle_depth)
+        .layer(interface_depth)
+}
+
+/// Сборка атласа из мелких изображений интерфейса и кадров экрана загрузки.
+fn load_ui_atlas(manifest:&ResourceManifest)->GameResult<Atlas>{
+    let mut builder=AtlasBuilder::new(ui_atlas_size).padding(ui_atlas_padding);
 
-    Icon::from_rgba(vec,width,height).unwrap()
+    for name in ui_atlas_images.iter(){
+        let image=load_image(manifest.require_image(name)?,None)?;
+        builder.add(*name,image);
+    }
+
+    // Изображения остальных состояний курсора необязательны
+    for state in CursorState::all.iter().filter(|&&state|state!=CursorState::Default){
+        if let Some(path)=manifest.image(state.image_name()){
+            builder.add(state.image_name(),load_image(path,None)?);
+        }
+    }
+
+    let sheet=load_image(manifest.require_image("loading_screen")?,None)?;
+    builder.add_vertical_frames(&loading_screen_frames,&sheet);
+
+    let atlas=builder.build()?;
+    log::debug(format!("атлас интерфейса: {} изображений",atlas.regions().count()));
+    Ok(atlas)
 }
 
-// Загрузка изображений
-fn load_image<P:AsRef<Path>>(path:P,size:Option<[u32;2]>)->RgbaImage{
-    let mut image=cat_engine::image::open(path).unwrap();
+/// Размер и горячие точки курсора из оформления.
+fn cursor_settings()->CursorSettings{
+    unsafe{
+        let mut settings=CursorSettings::new(viewport.length(theme.cursor_size));
+        for &state in CursorState::all.iter(){
+            if let Some(hotspot)=theme.cursor_hotspots[state as usize]{
+                settings=settings.hotspot(state,hotspot);
+            }
+        }
+        settings
+    }
+}
+
+/// Размер картинки обоев для экрана размером `screen`
+/// (чуть больше экрана для сдвига за курсором).
+pub fn wallpaper_size(screen:[f32;2])->[u32;2]{
+    let dx=screen[0]/(wallpaper_depth*2f32);
+    let dy=screen[1]/(wallpaper_depth*2f32);
+    [
+        (screen[0]+2f32*dx).ceil() as u32,
+        (screen[1]+2f32*dy).ceil() as u32,
+    ]
+}
 
-    if let Some([width,height])=size{
-        image=image.resize_exact(width,height,cat_engine::image::imageops::FilterType::Gaussian);
+/// Положение обоев в окне.
+fn wallpaper_rect()->[f32;4]{
+    unsafe{
+        let dx=window_width/(wallpaper_depth*2f32);
+        let dy=window_height/(wallpaper_depth*2f32);
+        [-dx,-dy,window_width+2f32*dx,window_height+2f32*dy]
     }
+}
 
-    if let cat_engine::image::DynamicImage::ImageRgba8(image)=image{
-        image
+/// Объект во весь экран (обои, снимок для переходов).
+fn screen_object(image_base:&ImageObject,texture:&TextureObject,graphics:&mut Graphics2D)->GameResult<TexturedObject>{
+    TexturedObject::new(image_base,texture,graphics)
+            .ok_or_else(||GameError::failed("screen","не хватает места для текстурного объекта"))
+}
+
+/// Размер окна в оконном режиме - часть экрана с пропорциями виртуального экрана.
+fn windowed_size(screen:[f32;2])->Size{
+    let mut scale_viewport=Viewport::new(design_resolution);
+    scale_viewport.resize([screen[0]*windowed_scale,screen[1]*windowed_scale]);
+    let [width,height]=scale_viewport.size(design_resolution);
+
+    Size::Physical(PhysicalSize::new(width as u32,height as u32))
+}
+
+/// Переключение полноэкранного и оконного режимов.
+pub fn set_fullscreen(window:&Window,fullscreen:bool,screen:[f32;2]){
+    let gl_window=window.display().gl_window();
+    let window=gl_window.window();
+
+    if fullscreen{
+        window.set_decorations(false);
+        window.set_fullscreen(Some(Fullscreen::Borderless(window.current_monitor())));
     }
     else{
-        image.into_rgba8()
+        window.set_fullscreen(None);
+        window.set_decorations(true);
+        window.set_inner_size(windowed_size(screen));
     }
 }
 
-fn get_swipe_texture<'a,'b>(graphics:&'a mut Graphics2D)->&'b Texture{
-    unsafe{
-        let r=graphics.get_textured_object_texture(swipe_screen_index) as *mut Texture;
-        &*r
+/// Загрузка иконки окна
+fn load_window_icon<P:AsRef<Path>>(path:P)->GameResult<Icon>{
+    let path=path.as_ref();
+    let image=cat_engine::image::open(path).map_err(|e|GameError::file(path,e))?;
+    let vec=image.to_bytes();
+    let (width,height)=image.dimensions();
+
+    Icon::from_rgba(vec,width,height).map_err(|e|GameError::file(path,e))
+}
+
+/// Сохранение настроек после изменения (ошибка только записывается в журнал).
+pub fn save_settings(){
+    if let Err(error)=unsafe{game_settings.save()}{
+        log_error(&error);
     }
 }
 
-fn draw_on_texture<F:FnOnce(&mut Graphics<SimpleFrameBuffer>)>(
-    texture:&Texture,
-    window:&Window,
-    graphics:&Graphics2D,
-    f:F
-){
-    let mut frame_buffer=SimpleFrameBuffer::new(window.display(),&texture.0).unwrap();
-
-    let mut frame_buffer_graphics=Graphics{
-        graphics2d:graphics,
-        draw_parameters:default_draw_parameters(),
-        frame:&mut frame_buffer,
-    };
-    
-    f(&mut frame_buffer_graphics);
+/// Запись ошибки в журнал (ошибки также попадают в журнал рядом со снимками экрана).
+pub fn log_error(error:&GameError){
+    log::error(error.to_string());
 }
 
-fn make_screenshot(window:&Window,audio:&AudioWrapper){
-    unsafe{
-        audio.play_track("screenshot",1u32);
-        let path=format!("screenshots/screenshot{}.png",game_settings.screenshot);
-        game_settings.screenshot+=1;
-        window.save_screenshot(path);
+/// Снимок экрана - кодирование и запись файла идут в отдельном потоке,
+/// результат показывается сообщением (`GameContext::update_overlay`).
+fn make_screenshot(window:&Window,context:&GameContext){
+    match window.screenshot(){
+        Some(image)=>{
+            context.play_track("screenshot",1u32);
+            context.screenshots.save(image);
+        }
+        None=>log::warning("снимок экрана не получен"),
     }
 }
 
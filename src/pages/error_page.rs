@@ -0,0 +1,157 @@
+use crate::{
+    // statics
+    localization,
+    viewport,
+    theme,
+    // consts
+    game_name,
+    // structs
+    GameContext,
+    // types
+    GameSceneAction,
+    // fns
+    log_error,
+};
+
+use lib::{
+    AlignY,
+    error::GameError,
+    scene::{Scene,SceneAction},
+    user_interface::{
+        TextView,
+        TextViewSettings,
+        GeneralSettings,
+        VStack,
+    },
+};
+
+use cat_engine::{
+    // enums
+    WindowEvent,
+    // structs
+    Window,
+    graphics::{Graphics,Graphics2D},
+    text::{Scale,FontOwner,GlyphCache,CachedFont},
+
+    glium::{
+        Frame,
+        Surface,
+        framebuffer::SimpleFrameBuffer,
+    },
+};
+
+/// Высота строк сообщения (в виртуальных пикселях)
+const line_height:f32=40f32;
+
+/// Строка интерфейса или `fallback`, если строки ещё не загружены.
+fn text(key:&str,fallback:&str)->String{
+    let line=unsafe{localization.get(key)};
+    if line==key{
+        fallback.to_string()
+    }
+    else{
+        line
+    }
+}
+
+/// Страница ошибки - какой файл не удалось загрузить и почему.
+///
+/// Ошибка записывается в журнал. Шрифты могут быть ещё не загружены,
+/// поэтому главный шрифт загружается здесь только для символов сообщения.
+/// Если и это не удаётся, сообщение выводится в заголовок окна.
+/// Любая клавиша или нажатие мыши закрывает игру.
+pub struct ErrorPage{
+    lines:Vec<String>,
+    /// Номер загруженного шрифта
+    font:Option<usize>,
+    views:Vec<TextView>,
+}
+
+impl ErrorPage{
+    pub fn new(window:&Window,graphics:&mut Graphics2D,context:&GameContext,error:&GameError)->ErrorPage{
+        log_error(error);
+
+        let mut lines=vec![text("loading_failed","Failed to load resources")];
+        if let Some(path)=error.path(){
+            lines.push(format!("{}: {}",text("error_file","File"),path.display()));
+        }
+        lines.push(error.reason());
+        lines.push(text("loading_exit_hint","Press any key to exit"));
+
+        let font=context.manifest.fonts.first().and_then(|entry|FontOwner::load(&entry.path));
+        let font=match font{
+            Some(font)=>{
+                let alphabet:String=lines.concat();
+                let glyph_cache=GlyphCache::new_alphabet(font.face(),&alphabet,Scale::new(0.1f32,0.1f32),window.display());
+                Some(graphics.add_font(CachedFont::raw(font,glyph_cache)))
+            }
+            None=>{
+                window.display().gl_window().window().set_title(&format!("{} - {}",game_name,lines.join(" - ")));
+                None
+            }
+        };
+
+        let mut page=Self{
+            lines,
+            font,
+            views:Vec::new(),
+        };
+        page.build(graphics);
+        page
+    }
+
+    /// Строки по центру экрана, первая - цветом ошибки,
+    /// остальные - цветом подписей (светлым на тёмном фоне страницы).
+    fn build(&mut self,graphics:&mut Graphics2D){
+        self.views.clear();
+
+        let font=match self.font{
+            Some(font)=>font,
+            None=>return,
+        };
+
+        let (area,height)=unsafe{(viewport.area(),viewport.length(line_height))};
+        let sizes=vec![[area[2],height];self.lines.len()];
+        let rects=VStack::new(GeneralSettings::new(area))
+                .align_y(AlignY::Center)
+                .rects(&sizes);
+
+        for (c,(line,rect)) in self.lines.iter().zip(rects).enumerate(){
+            let colour=unsafe{if c==0{theme.error_colour}else{theme.label_colour}};
+            let settings=TextViewSettings::new(line.as_str(),GeneralSettings::new(rect),unsafe{&theme})
+                    .font(font)
+                    .font_size(height*0.6f32)
+                    .text_colour(colour);
+            self.views.push(TextView::new(settings,graphics));
+        }
+    }
+
+    fn render<S:Surface>(&self,graphics:&mut Graphics<S>){
+        graphics.clear_colour(unsafe{theme.page_colour});
+        for view in &self.views{
+            view.draw(graphics);
+        }
+    }
+}
+
+impl Scene<GameContext> for ErrorPage{
+    fn resize(&mut self,_context:&mut GameContext,_window:&mut Window,graphics:&mut Graphics2D)->GameSceneAction{
+        self.build(graphics);
+        SceneAction::None
+    }
+
+    fn input(&mut self,event:WindowEvent,_context:&mut GameContext,_window:&mut Window,_graphics:&mut Graphics2D)->GameSceneAction{
+        match event{
+            WindowEvent::KeyboardPressed(_) | WindowEvent::MousePressed(_)=>SceneAction::Exit,
+            _=>SceneAction::None,
+        }
+    }
+
+    fn draw(&self,_shift:[f32;2],_context:&GameContext,graphics:&mut Graphics<Frame>){
+        self.render(graphics)
+    }
+
+    fn draw_on_texture(&self,_context:&GameContext,graphics:&mut Graphics<SimpleFrameBuffer>){
+        self.render(graphics)
+    }
+}
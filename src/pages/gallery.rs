@@ -0,0 +1,442 @@
+use crate::{
+    // consts
+    screenshot_directory,
+    // statics
+    resources,
+    localization,
+    viewport,
+    theme,
+    // structs
+    GameContext,
+    // types
+    GameSceneAction,
+    // fns
+    log_error,
+};
+
+use lib::{
+    colours::White,
+    error::{GameError,GameResult},
+    loader::Loader,
+    scene::{Scene,SceneAction},
+    cursor::CursorState,
+    objects::{TextureObject,TexturedObject},
+    screenshot::{screenshots,load_thumbnail},
+    viewport::Anchor,
+    user_interface::{
+        Button,
+        ButtonSettings,
+        GeneralSettings,
+        Grid,
+        TextView,
+        TextViewSettings,
+    },
+};
+
+use cat_engine::{
+    // statics
+    mouse_cursor,
+    // enums
+    KeyboardButton,
+    // structs
+    Window,
+    WindowEvent,
+    MouseButton,
+    graphics::{Graphics,Graphics2D},
+    texture::{ImageObject,Texture},
+    image::{RgbaImage,GenericImageView},
+
+    glium::{
+        Frame,
+        Surface,
+        framebuffer::SimpleFrameBuffer,
+    },
+};
+
+use std::path::PathBuf;
+
+/// Столбцы и строки миниатюр на странице галереи
+const gallery_columns:usize=4usize;
+const gallery_rows:usize=3usize;
+
+/// Виртуальный размер ячейки миниатюры и расстояние между ячейками
+const thumbnail_size:[f32;2]=[400f32,225f32];
+const thumbnail_spacing:f32=40f32;
+
+/// Виртуальный размер области просмотра снимка
+const view_size:[f32;2]=[1760f32,990f32];
+
+/// Изображение снимка в окне.
+struct Picture{
+    object:TexturedObject,
+    // Текстура удаляется после объекта
+    _texture:TextureObject,
+    rect:[f32;4],
+}
+
+impl Picture{
+    /// Изображение по центру области `area` в натуральную величину.
+    fn new(image:&RgbaImage,area:[f32;4],window:&Window,graphics:&mut Graphics2D)->GameResult<Picture>{
+        let texture=Texture::from_image(image,window.display()).map_err(GameError::system)?;
+        let texture=TextureObject::new(texture,graphics);
+
+        let (width,height)=image.dimensions();
+        let [width,height]=[width as f32,height as f32];
+        let rect=[
+            area[0]+(area[2]-width)/2f32,
+            area[1]+(area[3]-height)/2f32,
+            width,
+            height,
+        ];
+
+        let image_base=ImageObject::new(rect,[0f32,0f32,1f32,1f32],White);
+        let object=TexturedObject::new(&image_base,&texture,graphics)
+                .ok_or_else(||GameError::failed("gallery","не хватает места для изображения"))?;
+
+        Ok(Self{
+            object,
+            _texture:texture,
+            rect,
+        })
+    }
+
+    fn in_area(&self,x:f32,y:f32)->bool{
+        let [left,top,width,height]=self.rect;
+        x>=left && x<=left+width && y>=top && y<=top+height
+    }
+}
+
+/// Размер области в пикселях для уменьшения снимков.
+fn pixel_size(area:[f32;4])->[u32;2]{
+    [area[2] as u32,area[3] as u32]
+}
+
+/// Галерея снимков экрана: миниатюры по страницам и просмотр снимка.
+///
+/// Снимки декодируются в отдельном потоке, поэтому страница
+/// открывается сразу, а миниатюры появляются по готовности.
+pub struct Gallery{
+    /// Снимки экрана, новые первыми
+    files:Vec<PathBuf>,
+    /// Номер страницы миниатюр
+    page:usize,
+    /// Ячейки миниатюр страницы
+    cells:Vec<[f32;4]>,
+    thumbnails:Vec<Option<Picture>>,
+    thumbnail_loader:Option<Loader<Vec<Option<RgbaImage>>>>,
+
+    /// Номер открытого снимка
+    viewed:Option<usize>,
+    view:Option<Picture>,
+    view_loader:Option<Loader<RgbaImage>>,
+
+    title:TextView,
+    escape:Button,
+    escape_pressed:bool,
+}
+
+impl Gallery{
+    pub fn new(_window:&Window,graphics:&mut Graphics2D,page:usize)->Gallery{
+        let files=screenshots(unsafe{resources.user_path(screenshot_directory)});
+
+        let padding=unsafe{theme.page_padding};
+
+        let escape_rect=unsafe{viewport.anchored(Anchor::BottomLeft,[padding,-padding],[160f32,60f32])};
+        let escape_settings=ButtonSettings::new(unsafe{localization.get("gallery_back")},escape_rect,unsafe{&theme})
+                .font_size(unsafe{viewport.length(theme.button_font_size)});
+        let escape=Button::new(escape_settings,graphics);
+
+        let title_rect=unsafe{viewport.anchored(Anchor::Top,[0f32,padding],[800f32,60f32])};
+        let title_settings=TextViewSettings::new("",GeneralSettings::new(title_rect),unsafe{&theme})
+                .font_size(unsafe{viewport.length(theme.header_font_size)})
+                .text_colour(unsafe{theme.label_colour});
+        let title=TextView::new(title_settings,graphics);
+
+        // Сетка миниатюр по центру экрана
+        let cells=unsafe{
+            Grid::new(GeneralSettings::new(viewport.area()),gallery_columns,viewport.size(thumbnail_size))
+                .spacing(viewport.length(thumbnail_spacing),viewport.length(thumbnail_spacing))
+                .rects(gallery_columns*gallery_rows)
+        };
+
+        let mut gallery=Self{
+            files,
+            page:0usize,
+            cells,
+            thumbnails:Vec::new(),
+            thumbnail_loader:None,
+
+            viewed:None,
+            view:None,
+            view_loader:None,
+
+            title,
+            escape,
+            escape_pressed:false,
+        };
+        gallery.load_page(page,graphics);
+        gallery
+    }
+
+    fn page_count(&self)->usize{
+        let per_page=gallery_columns*gallery_rows;
+        ((self.files.len()+per_page-1)/per_page).max(1usize)
+    }
+
+    /// Номер первого снимка страницы.
+    fn first_on_page(&self)->usize{
+        self.page*gallery_columns*gallery_rows
+    }
+
+    /// Переход на страницу `page` и загрузка её миниатюр.
+    fn load_page(&mut self,page:usize,graphics:&mut Graphics2D){
+        self.page=page.min(self.page_count()-1usize);
+
+        self.thumbnail_loader=None;
+        self.thumbnails.clear();
+
+        let title=unsafe{
+            if self.files.is_empty(){
+                localization.get("gallery_empty")
+            }
+            else{
+                format!("{} {}/{}",localization.get("gallery_title"),self.page+1usize,self.page_count())
+            }
+        };
+        self.title.set_text(title,graphics);
+
+        let first=self.first_on_page();
+        let files:Vec<PathBuf>=self.files.iter().skip(first).take(self.cells.len()).cloned().collect();
+        if files.is_empty(){
+            return
+        }
+
+        let size=pixel_size(self.cells[0]);
+        self.thumbnail_loader=Some(Loader::spawn(move|progress|{
+            progress.set_total(files.len());
+
+            let mut thumbnails=Vec::with_capacity(files.len());
+            for path in files{
+                progress.begin(&path.to_string_lossy())?;
+                // Повреждённый снимок не мешает показать остальные
+                let thumbnail=match load_thumbnail(&path,size){
+                    Ok(thumbnail)=>Some(thumbnail),
+                    Err(error)=>{
+                        log_error(&error);
+                        None
+                    }
+                };
+                thumbnails.push(thumbnail);
+                progress.finish_item();
+            }
+            Ok(thumbnails)
+        }));
+    }
+
+    /// Просмотр снимка с номером `index`.
+    fn open(&mut self,index:usize){
+        let path=match self.files.get(index){
+            Some(path)=>path.clone(),
+            None=>return,
+        };
+
+        self.view_loader=None;
+        self.view=None;
+
+        self.viewed=Some(index);
+
+        let size=pixel_size(unsafe{viewport.anchored(Anchor::Center,[0f32;2],view_size)});
+        self.view_loader=Some(Loader::spawn(move|progress|{
+            progress.begin(&path.to_string_lossy())?;
+            load_thumbnail(&path,size)
+        }));
+    }
+
+    /// Возвращение к миниатюрам на странице открытого снимка.
+    fn close(&mut self,graphics:&mut Graphics2D){
+        self.view_loader=None;
+        self.view=None;
+
+        if let Some(index)=self.viewed.take(){
+            let page=index/(gallery_columns*gallery_rows);
+            if page!=self.page{
+                self.load_page(page,graphics);
+                return
+            }
+        }
+    }
+
+    /// Номер снимка, миниатюра которого под курсором.
+    fn thumbnail_at(&self,x:f32,y:f32)->Option<usize>{
+        self.thumbnails.iter()
+            .position(|thumbnail|thumbnail.as_ref().map_or(false,|thumbnail|thumbnail.in_area(x,y)))
+            .map(|index|self.first_on_page()+index)
+    }
+
+    /// Создание изображений из декодированных снимков.
+    fn take_loaded(&mut self,window:&Window,graphics:&mut Graphics2D){
+        let loaded=self.thumbnail_loader.as_mut().and_then(|loader|loader.take());
+        if let Some(result)=loaded{
+            self.thumbnail_loader=None;
+            match result{
+                Ok(images)=>{
+                    for (image,&cell) in images.iter().zip(self.cells.iter()){
+                        let thumbnail=image.as_ref().and_then(|image|{
+                            Picture::new(image,cell,window,graphics).map_err(|error|log_error(&error)).ok()
+                        });
+                        self.thumbnails.push(thumbnail);
+                    }
+                }
+                Err(error)=>log_error(&error),
+            }
+        }
+
+        let loaded=self.view_loader.as_mut().and_then(|loader|loader.take());
+        if let Some(result)=loaded{
+            self.view_loader=None;
+            let area=unsafe{viewport.anchored(Anchor::Center,[0f32;2],view_size)};
+            match result.and_then(|image|Picture::new(&image,area,window,graphics)){
+                Ok(view)=>self.view=Some(view),
+                Err(error)=>{
+                    log_error(&error);
+                    self.close(graphics);
+                }
+            }
+        }
+    }
+
+    fn cursor_state(&self)->CursorState{
+        if self.view_loader.is_some(){
+            return CursorState::Busy
+        }
+
+        let [x,y]=unsafe{mouse_cursor.position()};
+        if self.viewed.is_none() && (self.escape.in_area(x,y) || self.thumbnail_at(x,y).is_some()){
+            CursorState::Hover
+        }
+        else{
+            CursorState::Default
+        }
+    }
+
+    /// Переход на соседнюю страницу или к соседнему снимку (`step` - -1 или 1).
+    fn step(&mut self,step:isize,graphics:&mut Graphics2D){
+        match self.viewed{
+            Some(index)=>{
+                let next=index as isize+step;
+                if next>=0 && (next as usize)<self.files.len(){
+                    self.open(next as usize);
+                }
+            }
+            None=>{
+                let next=self.page as isize+step;
+                if next>=0 && (next as usize)<self.page_count(){
+                    self.load_page(next as usize,graphics);
+                }
+            }
+        }
+    }
+
+    fn render<S:Surface>(&self,shift:[f32;2],graphics:&mut Graphics<S>){
+        if self.viewed.is_some(){
+            if let Some(view)=&self.view{
+                view.object.draw_shift(shift,graphics);
+            }
+            return
+        }
+
+        self.title.draw_shift(shift,graphics);
+        for thumbnail in self.thumbnails.iter().flatten(){
+            thumbnail.object.draw_shift(shift,graphics);
+        }
+        self.escape.draw_shift(shift,graphics);
+    }
+}
+
+impl Scene<GameContext> for Gallery{
+    fn resize(&mut self,_context:&mut GameContext,_window:&mut Window,_graphics:&mut Graphics2D)->GameSceneAction{
+        // Страница строится заново под новый размер окна
+        let page=self.page;
+        SceneAction::replace(move|_context:&mut GameContext,window:&mut Window,graphics:&mut Graphics2D|{
+            Box::new(Gallery::new(window,graphics,page)) as Box<dyn Scene<GameContext>>
+        })
+    }
+
+    fn update(&mut self,_elapsed:f32,context:&mut GameContext,window:&mut Window,graphics:&mut Graphics2D)->GameSceneAction{
+        self.take_loaded(window,graphics);
+
+        context.cursor.set_state(self.cursor_state());
+
+        SceneAction::None
+    }
+
+    fn input(&mut self,event:WindowEvent,context:&mut GameContext,_window:&mut Window,graphics:&mut Graphics2D)->GameSceneAction{
+        match event{
+            WindowEvent::MousePressed(MouseButton::Left)=>{
+                if self.viewed.is_some(){
+                    // Нажатие на снимок возвращает к миниатюрам
+                    self.close(graphics);
+                    return SceneAction::None
+                }
+
+                let [x,y]=unsafe{mouse_cursor.position()};
+                if self.escape.pressed(x,y){
+                    context.play_track("button_pressed",1u32);
+                    self.escape.set_background_colour(unsafe{theme.button_colours.pressed},graphics);
+                    self.escape_pressed=true;
+                }
+                else if let Some(index)=self.thumbnail_at(x,y){
+                    self.open(index);
+                }
+            }
+
+            WindowEvent::MouseReleased(MouseButton::Left)=>if self.escape_pressed{
+                self.escape_pressed=false;
+                self.escape.set_background_colour(unsafe{theme.button_colours.normal},graphics);
+
+                let [x,y]=unsafe{mouse_cursor.position()};
+                if self.escape.released(x,y){
+                    return SceneAction::Pop
+                }
+            }
+
+            // Прокрутка вверх - предыдущая страница
+            WindowEvent::MouseWheelScroll(delta)=>{
+                if delta>0f32{
+                    self.step(-1isize,graphics);
+                }
+                else if delta<0f32{
+                    self.step(1isize,graphics);
+                }
+            }
+
+            WindowEvent::KeyboardPressed(button)=>match button{
+                KeyboardButton::Left=>self.step(-1isize,graphics),
+                KeyboardButton::Right=>self.step(1isize,graphics),
+
+                KeyboardButton::Escape=>if self.viewed.is_some(){
+                    self.close(graphics);
+                }
+                else{
+                    return SceneAction::Pop
+                }
+
+                _=>{}
+            }
+
+            _=>{}
+        }
+
+        SceneAction::None
+    }
+
+    fn draw(&self,shift:[f32;2],_context:&GameContext,graphics:&mut Graphics<Frame>){
+        graphics.clear_colour(unsafe{theme.page_colour});
+        self.render(shift,graphics);
+    }
+
+    fn draw_on_texture(&self,_context:&GameContext,graphics:&mut Graphics<SimpleFrameBuffer>){
+        graphics.clear_colour(unsafe{theme.page_colour});
+        self.render([0f32;2],graphics);
+    }
+}
@@ -1,48 +1,56 @@
 use crate::{
     // statics
     game_settings,
+    resources,
+    viewport,
+    theme,
     // consts
-    alphabet,
-    fonts_paths,
-    audio_tracks_paths,
-    main_menu_wallpaper_path,
-    decoration_image_paths,
-    wallpaper_movement_scale,
-    mouse_cursor_icon_index,
-    swipe_screen_index,
-    // enums
-    Game,
+    game_name,
     // structs
+    GameContext,
     LoadingMainData,
-    // fns
-    load_image,
-    draw_on_texture,
-    get_swipe_texture,
-    make_screenshot,
+    // types
+    GameSceneAction,
+};
+
+use super::{
+    MainMenu,
+    ErrorPage,
+    swipe,
 };
 
 use lib::{
     colours::White,
-    loading_flag::{
-        ThreadState,
-        LoadingFlag,
-        LoadingFlagSmartPtr
-    }
+    manifest::ResourceManifest,
+    localization::StringTable,
+    page_table::dialogue_lines,
+    player_name::NameSubstitution,
+    glyphs::CharacterSet,
+    scene::{Scene,SceneAction},
+    transition::SwipeDirection,
+    animation::{Tween,Repeat,SpriteAnimation},
+    atlas::Atlas,
+    viewport::Anchor,
+    objects::{TextureObject,TexturedObject},
+    error::{GameError,GameResult},
+    log,
+    loader::Loader,
+    cursor::CursorState,
+    user_interface::{
+        ProgressBar,
+        ProgressBarSettings,
+    },
 };
 
 use cat_engine::{
     // statics
-    mouse_cursor,
     window_center,
     window_width,
     window_height,
-    // functions
-    default_draw_parameters,
     // enums
-    KeyboardButton,
+    WindowEvent,
     // structs
     Window,
-    WindowEvent,
     graphics::{
         Graphics,
         Graphics2D,
@@ -50,291 +58,327 @@ use cat_engine::{
     },
     texture::{ImageObject,Texture},
     text::{Scale,FontOwner,GlyphCache,CachedFont},
-    audio::{ChanneledTrack,AudioWrapper},
+    audio::ChanneledTrack,
 
     glium::{
+        Frame,
         Surface,
         framebuffer::SimpleFrameBuffer
     },
 };
 
 use std::{
-    thread::{spawn,JoinHandle},
-    sync::mpsc::{
-        channel,
-        Receiver,
-        TryRecvError
-    },
+    fs::read_to_string,
+    path::Path,
 };
 
 const chars_chached_per_update:u8=10u8;
 
+/// Скорость вращения шестерни в радианах в секунду (около 140 градусов)
+const gear_speed:f32=2.5f32;
+
+/// Имена кадров картинки `loading_screen` (друг под другом сверху вниз)
+/// в атласе интерфейса
+pub const loading_screen_frames:[&'static str;4]=["gear","cat_eyes_closed","cat_eyes_half_closed","cat"];
+
+// Размеры в виртуальных пикселях
+const gear_size:[f32;2]=[400f32;2];
+const cat_size:[f32;2]=[200f32;2];
+const progress_bar_size:[f32;2]=[600f32,16f32];
+/// Отступ полосы загрузки от нижнего края
+const progress_bar_offset:f32=-120f32;
+
+fn progress_bar_rect()->[f32;4]{
+    unsafe{viewport.anchored(Anchor::Bottom,[0f32,progress_bar_offset],progress_bar_size)}
+}
+
+fn progress_bar_settings()->ProgressBarSettings{
+    ProgressBarSettings::new(progress_bar_rect(),unsafe{&theme})
+}
+
+/// Моргание кота: 0.7 секунды глаза открыты, 0.3 секунды - моргание.
+fn cat_animation(atlas:&Atlas)->GameResult<SpriteAnimation>{
+    let cat=atlas.require("cat")?.uv;
+    let half_closed=atlas.require("cat_eyes_half_closed")?.uv;
+    let closed=atlas.require("cat_eyes_closed")?.uv;
+
+    Ok(SpriteAnimation::new(cat,0.7f32)
+        .frame(half_closed,0.1f32)
+        .frame(closed,0.1f32)
+        .frame(half_closed,0.1f32)
+        .repeat(Repeat::Loop))
+}
+
+/// Вращение шестерни - полный оборот по кругу.
+fn gear_animation()->Tween<f32>{
+    let turn=2f32*std::f32::consts::PI;
+    Tween::new(0f32).to(turn,turn/gear_speed).repeat(Repeat::Loop)
+}
+
 pub struct LoadingScreen{
-    cat:usize,
+    cat:TexturedObject,
     cat_image_base:ImageObject,
-    gear:usize,
-    thread:Option<JoinHandle<LoadingMainData>>,
-    thread_flag:LoadingFlag,
+    gear:TexturedObject,
+    gear_image_base:ImageObject,
+    loader:Loader<LoadingMainData>,
+    progress_bar:ProgressBar,
+
+    cat_animation:SpriteAnimation,
+    gear_animation:Tween<f32>,
+
+    // Кэширование шрифтов
+    caching_fonts:bool,
+    // Номер следующего символа для кэширования
+    character:usize,
+    current_font:Option<FontOwner>,
+    font_iter:Option<std::vec::IntoIter<FontOwner>>,
+    glyph_cache:Option<GlyphCache>,
+    scale:Scale,
 }
 
 impl LoadingScreen{
-    pub fn new(window:&Window,graphics:&mut Graphics2D)->LoadingScreen{
-        // Создание основы для иконки загрузки
-        let loading_screen_assets=Texture::from_path("./resources/images/loading_screen_assets.png",window.display()).unwrap();
-        let loading_screen_assets=graphics.add_texture(loading_screen_assets);
+    pub fn new(_window:&Window,graphics:&mut Graphics2D,context:&mut GameContext)->GameResult<LoadingScreen>{
+        // Изображения главного меню декодируются вместе с загрузкой
+        MainMenu::request_assets(context);
 
         // Шестерня
-        let mut image_base=ImageObject::raw_uv(unsafe{[
-                window_center[0]-200f32,
-                window_center[1]-200f32,
-                400f32,
-                400f32
-            ]},
-            [
-                0f32,
-                0f32,
-                1f32,
-                1f32/4f32,
-            ],
+        let (gear_texture,gear_uv)=context.atlas_image("gear")?;
+        let gear_image_base=ImageObject::raw_uv(
+            unsafe{viewport.anchored(Anchor::Center,[0f32;2],gear_size)},
+            gear_uv,
             White
         );
-        let gear=graphics.add_textured_object(&image_base,loading_screen_assets).unwrap();
+        let gear=TexturedObject::new(&gear_image_base,gear_texture,graphics)
+                .ok_or_else(||GameError::failed("gear","не хватает места для изображения"))?;
 
         // Кот
-        image_base.set_new_raw_uv(unsafe{[
-                window_center[0]-100f32,
-                window_center[1]-100f32,
-                200f32,
-                200f32
-            ]},
-            [
-                0f32,
-                3f32/4f32,
-                1f32,
-                1f32,
-            ],
+        let cat_animation=cat_animation(&context.atlas)?;
+        let (cat_texture,_)=context.atlas_image("cat")?;
+        let cat_image_base=ImageObject::raw_uv(
+            unsafe{viewport.anchored(Anchor::Center,[0f32;2],cat_size)},
+            cat_animation.uv(),
             White
         );
-        let cat=graphics.add_textured_object(&image_base,loading_screen_assets).unwrap();
-
-        let mut loading_flag=LoadingFlag::new();
-        let loading_flag_ptr=loading_flag.ptr();
-        let thread=spawn(move||{
-            // Для переноса в поток
-            let loading_flag=loading_flag_ptr;
+        let cat=TexturedObject::new(&cat_image_base,cat_texture,graphics)
+                .ok_or_else(||GameError::failed("cat","не хватает места для изображения"))?;
 
+        let manifest=context.manifest.clone();
+        let resolver=unsafe{resources.clone()};
+        let user_name=unsafe{game_settings.user_name.clone()};
+        let loader=Loader::spawn(move|progress|{
             let mut data=LoadingMainData::new();
 
+            progress.set_total(manifest.fonts.len()+manifest.audio.len()+manifest.languages.len());
+
             let mut fonts=Vec::new();
             // Загрузка шрифтов
-            for path in fonts_paths{
-                if let ThreadState::Finished=loading_flag.get_state(){
-                    return data
-                }
-                let font=FontOwner::load(path).unwrap();
+            for entry in &manifest.fonts{
+                progress.begin(&entry.name)?;
+                let font=FontOwner::load(&entry.path)
+                        .ok_or_else(||GameError::file(&entry.path,"не удалось загрузить шрифт"))?;
                 fonts.push(font);
+                progress.finish_item();
             }
 
+            // Без шрифтов нечем выводить текст
+            if fonts.is_empty(){
+                return Err(GameError::missing("fonts","main"))
+            }
             data.fonts=Some(fonts);
 
             // Загрузка аудио
-            for path in audio_tracks_paths{
-                if let ThreadState::Finished=loading_flag.get_state(){
-                    return data
-                }
-                let audio=ChanneledTrack::new(path).unwrap();
+            for entry in &manifest.audio{
+                progress.begin(&entry.name)?;
+                let audio=ChanneledTrack::new(&entry.path)
+                        .map_err(|e|GameError::file(&entry.path,e))?;
                 data.audio.push(audio);
+                progress.finish_item();
             }
 
-            if let ThreadState::Finished=loading_flag.get_state(){
-                return data
-            }
-            // Размеры изображения для обоев
-            let (width,height)=unsafe{
-                let dx=window_width/(wallpaper_movement_scale*2f32);
-                let dy=window_height/(wallpaper_movement_scale*2f32);
-                let width=(window_width+2f32*dx).ceil();
-                let height=(window_height+2f32*dy).ceil();
-
-                (width as u32,height as u32)
-            };
-            // Загрузка обоев главного меню
-            let main_menu_wallpaper=load_image(main_menu_wallpaper_path,Some([width,height]));
-            data.textures.push(main_menu_wallpaper);
-
-            for path in decoration_image_paths{
-                let image=load_image(path,None);
-                data.textures.push(image);
+            // Сбор символов из строк интерфейса и диалогов всех языков
+            let mut characters=CharacterSet::new();
+            characters.insert_str(game_name);
+            for entry in &manifest.languages{
+                progress.begin(&entry.name)?;
+                let strings=StringTable::load(&entry.path)?;
+                for line in strings.values(){
+                    characters.insert_str(line);
+                }
+
+                // Реплики выводятся уже с подставленными именами
+                let substitution=NameSubstitution::new(
+                    user_name.as_str(),
+                    strings.get("player_pronoun").unwrap_or_default()
+                );
+                for dialogue in resolver.files(Path::new("dialogues").join(&entry.name)){
+                    let text=read_to_string(&dialogue).map_err(|e|GameError::file(&dialogue,e))?;
+                    for line in dialogue_lines(&text,&substitution).map_err(|e|GameError::file(&dialogue,e))?{
+                        characters.insert_str(&line.speaker);
+                        characters.insert_str(&line.text);
+                    }
+                }
+                progress.finish_item();
             }
+            data.characters=characters.chars().collect();
 
             // Передача данных
-            data
+            Ok(data)
         });
 
-        Self{
+        context.cursor.set_state(CursorState::Busy);
+
+        Ok(Self{
             cat,
-            cat_image_base:image_base,
+            cat_image_base,
             gear,
-            thread:Some(thread),
-            thread_flag:loading_flag,
+            gear_image_base,
+            loader,
+            progress_bar:ProgressBar::new(progress_bar_settings(),graphics),
+
+            cat_animation,
+            gear_animation:gear_animation(),
+
+            caching_fonts:false,
+            character:0usize,
+            current_font:None,
+            font_iter:None,
+            glyph_cache:None,
+            scale:Scale::new(0.1f32,0.1f32),
+        })
+    }
+
+    /// Завершение загрузки - запуск музыки и переход в главное меню.
+    fn finish(&mut self,context:&mut GameContext)->GameSceneAction{
+        log::info("ресурсы загружены");
+        context.cursor.set_state(CursorState::Default);
+        context.fonts_loaded=true;
+
+        // Загрузка треков в хранилище
+        let tracks=std::mem::replace(&mut context.data.audio,Vec::new());
+        if let Some(audio)=&mut context.audio{
+            for (track,entry) in tracks.into_iter().zip(context.manifest.audio.iter()){
+                audio.push_track(track,entry.name.clone());
+            }
         }
+
+        // Запуск мелодии главной темы (повторять бесконечно)
+        context.play_track("main_theme",0u32);
+
+        MainMenu::open().with_transition(swipe(SwipeDirection::Left))
     }
 
-    pub fn run(&mut self,window:&mut Window,graphics:&mut Graphics2D,audio:&AudioWrapper,data:&mut LoadingMainData)->Game{
-        let mut frames=0u8;
-        let mut angle=0f32;
+    fn render<S:Surface>(&self,graphics:&mut Graphics<S>){
+        graphics.clear_colour(unsafe{theme.loading_colour});
+        // Рендеринг кота
+        self.cat.draw(graphics);
+        // Рендеринг шестерни
+        self.gear.draw_rotate(unsafe{window_center},self.gear_animation.value(),graphics);
+        // Ход загрузки
+        self.progress_bar.draw(graphics);
+    }
+}
 
-        let mut result=Game::Next;
+impl Scene<GameContext> for LoadingScreen{
+    fn exit(&mut self,_context:&mut GameContext,_window:&mut Window,_graphics:&mut Graphics2D){
+        // Остановка загрузки при закрытии игры
+        self.loader.cancel();
+    }
 
-        // Кэширование шрифтов
-        let mut caching_fonts=false;
+    fn resize(&mut self,_context:&mut GameContext,_window:&mut Window,graphics:&mut Graphics2D)->GameSceneAction{
+        unsafe{
+            self.gear_image_base.set_rect(viewport.anchored(Anchor::Center,[0f32;2],gear_size));
+            self.cat_image_base.set_rect(viewport.anchored(Anchor::Center,[0f32;2],cat_size));
+        }
+        self.gear.rewrite_vertices(&self.gear_image_base,graphics);
+        self.cat.rewrite_vertices(&self.cat_image_base,graphics);
 
-        let mut alphabet_iter=alphabet.chars();
+        // Фигуры нельзя перестроить - полоса создаётся заново
+        let progress=self.progress_bar.progress();
+        self.progress_bar=ProgressBar::new(progress_bar_settings(),graphics);
+        self.progress_bar.set_progress(progress);
 
-        let mut current_font:Option<FontOwner>=None;
-        let mut font_iter:Option<std::vec::IntoIter<cat_engine::text::FontOwner>>=None;
+        SceneAction::None
+    }
 
-        let scale=Scale::new(0.1f32,0.1f32);
+    fn update(&mut self,elapsed:f32,context:&mut GameContext,window:&mut Window,graphics:&mut Graphics2D)->GameSceneAction{
+        // Анимация загрузки
+        if self.cat_animation.update(elapsed){
+            self.cat_image_base.set_raw_uv(self.cat_animation.uv());
+            self.cat.rewrite_vertices(&self.cat_image_base,graphics);
+        }
 
-        let mut glyph_cache:Option<GlyphCache>=None;
+        self.gear_animation.update(elapsed);
 
-        window.run(|window,event|{
-            match event{
-                WindowEvent::CloseRequested=>{
-                    self.thread_flag.set_state(ThreadState::Finished);
-                    if let Some(thread)=self.thread.take(){
-                        thread.join().expect("Ошибка начальной загрузки");
-                    }
-                    result=Game::Exit;
-                }
+        self.progress_bar.set_progress(self.loader.progress().fraction());
 
-                WindowEvent::Update=>{
-                    // Анимация загрузки
-                    frames+=1;
-                    // 0.35 секунды глаза открыты, 0.15 секунды закрыты
-                    if frames==35{
-                        // cat_eyes_half_closed
-                        self.cat_image_base.set_raw_uv([0f32,2f32/4f32,1f32,3f32/4f32]);
-                        graphics.rewrite_textured_object_vertices(self.cat,&self.cat_image_base.vertices());
-                    }
-                    else if frames==40{
-                        // cat_eyes_closed
-                        self.cat_image_base.set_raw_uv([0f32,1f32/4f32,1f32,2f32/4f32]);
-                        graphics.rewrite_textured_object_vertices(self.cat,&self.cat_image_base.vertices());
-                    }
-                    else if frames==45{
-                        // cat_eyes_half_closed
-                        self.cat_image_base.set_raw_uv([0f32,2f32/4f32,1f32,3f32/4f32]);
-                        graphics.rewrite_textured_object_vertices(self.cat,&self.cat_image_base.vertices());
-                    }
-                    else if frames==50{
-                        // cat
-                        self.cat_image_base.set_raw_uv([0f32,3f32/4f32,1f32,1f32]);
-                        graphics.rewrite_textured_object_vertices(self.cat,&self.cat_image_base.vertices());
-                        frames=0;
+        // Кэширование шрифтов
+        if self.caching_fonts{
+            if let Some(font)=&self.current_font{
+                let mut chars_passed=0;
+                while chars_passed<chars_chached_per_update{
+                    if let Some(&next_character)=context.data.characters.get(self.character){
+                        if let Some(glyph_cache)=&mut self.glyph_cache{
+                            glyph_cache.insert_char(next_character,font.face(),self.scale,window.display());
+                        }
+                        self.character+=1;
+                        chars_passed+=1;
                     }
+                    else{
+                        self.character=0;
+
+                        let font=self.current_font.take().unwrap();
 
-                    // 140 градусов в секунду
-                    angle+=0.05;
-
-                    // Кэширование шрифтов
-                    if caching_fonts{
-                        if let Some(font)=&current_font{
-                            let mut chars_passed=0;
-                            while chars_passed<chars_chached_per_update{
-                                if let Some(character)=alphabet_iter.next(){
-                                    if let Some(glyph_cache)=&mut glyph_cache{
-                                        glyph_cache.insert_char(character,font.face(),scale,window.display());
-                                    }
-                                    chars_passed+=1;
-                                }
-                                else{
-                                    alphabet_iter=alphabet.chars();
-
-                                    let font=current_font.take().unwrap();
-
-                                    let chached_font=CachedFont::raw(font,glyph_cache.take().unwrap());
-                                    graphics.add_font(chached_font);
-
-                                    current_font=font_iter.as_mut().unwrap().next();
-
-                                    if let Some(font)=&current_font{
-                                        glyph_cache=Some(GlyphCache::new_alphabet(font.face(),"",scale,window.display()));
-                                    }
-                                    else{
-                                        window.stop_events();
-                                    }
-                                    break
-                                }
-                            }
+                        let chached_font=CachedFont::raw(font,self.glyph_cache.take().unwrap());
+                        graphics.add_font(chached_font);
+
+                        self.current_font=self.font_iter.as_mut().unwrap().next();
+
+                        if let Some(font)=&self.current_font{
+                            self.glyph_cache=Some(GlyphCache::new_alphabet(font.face(),"",self.scale,window.display()));
                         }
-                    }
-                    else{ // Ожидание загрузки нужных ресурсов
-                        match self.thread_flag.get_state(){
-                            ThreadState::Running=>{}
-
-                            // Завершение загрузки - начало кэширования шрифтов
-                            ThreadState::Finished=>
-                                if let Some(thread)=self.thread.take(){
-                                    *data=thread.join().unwrap();
-
-                                    let mut fonts=data.fonts.take().unwrap().into_iter();
-                                    current_font=fonts.next();
-                                    font_iter=Some(fonts);
-
-                                    glyph_cache=Some(GlyphCache::new_alphabet(current_font.as_ref().unwrap().face(),"",scale,window.display()));
-
-                                    caching_fonts=true;
-                                }
-
-                            // Ошибка загрузки - закрытие игры
-                            ThreadState::Panicked=>
-                                if let Some(thread)=self.thread.take(){
-                                    thread.join();
-                                    window.stop_events();
-                                    result=Game::Exit;
-                                }
+                        else{
+                            return self.finish(context)
                         }
+                        break
                     }
-
-                }
-                WindowEvent::RedrawRequested=>{
-                    let [dx,dy]=unsafe{mouse_cursor.center_radius()};
-
-                    window.draw(graphics,|graphics|{
-                        graphics.clear_colour(White);
-                        // Рендеринг кота
-                        graphics.draw_textured_object(self.cat).unwrap();
-                        // Рендеринг шестерни
-                        graphics.draw_rotate_textured_object(self.gear,unsafe{window_center},angle).unwrap();
-                        // Рендеринг курсора
-                        graphics.draw_shift_textured_object(mouse_cursor_icon_index,[dx,dy]).unwrap();
-                    }).unwrap();
                 }
+            }
+        }
+        else{ // Ожидание загрузки нужных ресурсов
+            match self.loader.take(){
+                None=>{}
 
-                WindowEvent::KeyboardPressed(KeyboardButton::F5)=>make_screenshot(window,audio),
+                // Завершение загрузки - начало кэширования шрифтов
+                Some(Ok(data))=>{
+                    context.data=data;
 
-                _=>{}
-            }
-        });
+                    let mut fonts=context.data.fonts.take().unwrap().into_iter();
+                    self.current_font=fonts.next();
+                    self.font_iter=Some(fonts);
 
-        let swipe_screen_texture=get_swipe_texture(graphics);
+                    self.glyph_cache=Some(GlyphCache::new_alphabet(self.current_font.as_ref().unwrap().face(),"",self.scale,window.display()));
 
-        draw_on_texture(&swipe_screen_texture,window,graphics,|graphics|{
-            graphics.clear_colour(White);
-            // Рендеринг кота
-            graphics.draw_textured_object(self.cat).unwrap();
-            // Рендеринг шестерни
-            graphics.draw_rotate_textured_object(self.gear,unsafe{window_center},angle).unwrap();
-        });
+                    self.caching_fonts=true;
+                }
 
-        // Кот
-        graphics.remove_last_textured_object();
-        // Шестерня
-        graphics.remove_last_textured_object();
-        // loading_screen_asset
-        graphics.remove_last_texture();
+                // Ошибка загрузки - показ причины
+                Some(Err(error))=>{
+                    context.cursor.set_state(CursorState::Default);
+                    return SceneAction::replace(move|context:&mut GameContext,window:&mut Window,graphics:&mut Graphics2D|{
+                        Box::new(ErrorPage::new(window,graphics,context,&error)) as Box<dyn Scene<GameContext>>
+                    })
+                }
+            }
+        }
+
+        SceneAction::None
+    }
+
+    fn draw(&self,_shift:[f32;2],_context:&GameContext,graphics:&mut Graphics<Frame>){
+        self.render(graphics)
+    }
 
-        result
+    fn draw_on_texture(&self,_context:&GameContext,graphics:&mut Graphics<SimpleFrameBuffer>){
+        self.render(graphics)
     }
-}
\ No newline at end of file
+}
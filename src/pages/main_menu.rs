@@ -1,35 +1,54 @@
 use crate::{
     // consts
     game_name,
-    mouse_cursor_icon_index,
-    wallpaper_index,
-    wallpaper_movement_scale,
-    swipe_screen_index,
-    swipe_updates,
+    wallpaper_layer,
+    particle_layer,
+    interface_layer,
     // statics
     game_settings,
-    // enums
-    Game,
-    // functions
-    get_swipe_texture,
-    draw_on_texture,
-    make_screenshot
+    resources,
+    localization,
+    viewport,
+    theme,
+    // structs
+    GameContext,
+    // types
+    GameSceneAction,
+    // fns
+    wallpaper_size,
+    save_settings,
 };
 
 use super::{
     // structs
     Settings,
+    Gallery,
     // consts
-    button_pressed,
-    // enums
-    SwipeDirection,
+    // fns
+    swipe,
+    page_or_error,
 };
 
 use lib::{
-    colours::{White,Gray,Light_blue},
+    error::{GameError,GameResult},
+    assets::AssetKey,
+    player_name::{
+        NameError,
+        max_name_length,
+        validate_name,
+    },
+    scene::{Scene,SceneAction},
+    cursor::CursorState,
+    objects::{TextureObject,TexturedObject},
+    transition::SwipeDirection,
+    particles::{EmitterSettings,ParticleEmitter,ParticlePreset},
+    viewport::Anchor,
     user_interface::{
         Menu,
         MenuSettings,
+        TextView,
+        TextViewSettings,
+        GeneralSettings,
         EditTextView,
         EditTextViewSettings,
     },
@@ -49,530 +68,338 @@ use cat_engine::{
     Window,
     WindowEvent,
     MouseButton,
-    graphics::{Graphics2D,DependentObject},
+    graphics::{Graphics,Graphics2D,DependentObject},
     texture::{ImageObject,ImageBase,Texture},
     image::RgbaImage,
 
-    audio::AudioWrapper,
+    glium::{
+        Frame,
+        Surface,
+        framebuffer::SimpleFrameBuffer,
+    },
 };
 
 
+/// Зерно для лепестков (одинаковый узор при каждом запуске)
+const leaves_seed:u64=2021u64;
 
-const menu_movement_scale:f32=10f32;
-
-const leaf_movement_scale:f32=12f32;
-
-const x_max_speed:f32=3f32;
-const x_min_speed:f32=0f32;
-
-const y_max_speed:f32=2f32;
-const y_min_speed:f32=-1f32;
-
-const x_accelerate:f32=1f32/32f32;
-const y_accelerate:f32=1f32/32f32;
-
-const leaf_spawn_times:&[u16]=&[
-    44,153,10,93,
-    588,97,126,642,
-    323,65,23,224,
-    123,345,49,342,
-    164,32,471
-];
-const leaf_spawn_positions:&[f32]=&[
-    0.123f32,0.42f32,0.73f32,0.154f32,
-    0.223f32,0.96f32,0.5f32,0.3451f32,
-    0.67f32,0.12f32,0.7521f32,0.542f32,
-    0.241f32,0.82f32,0.87f32,0.4111f32
-];
-
-struct Leaf{
-    x:f32,
-    y:f32,
-    x_speed:f32,
-    y_speed:f32,
-    left_side:bool,
-    x_up:bool,
-    y_up:bool
-}
-
-impl Leaf{
-    pub fn new(x:f32)->Leaf{
-        Self{
-            x,
-            y:0f32,
-            x_speed:0f32,
-            y_speed:0f32,
-            // Движение из левой части в правую
-            // или из правой в левую
-            left_side:true,
-            // Ускорение или замедление
-            x_up:true,
-            y_up:true
-        }
-    }
-}
+/// Количество страниц, обои и персонажи которых загружаются заранее
+const prefetched_pages:usize=2usize;
 
 pub struct MainMenu{
-    leaf:usize,
-    leaves:Vec<Leaf>,
+    leaves:ParticleEmitter,
     menu:Menu,
     enter_name:bool,
+    // Подсказка или ошибка ввода имени
+    name_prompt:TextView,
     user_name:EditTextView,
+
+    // Обои декодируются в потоках загрузки и показываются, когда готовы
+    wallpaper_ready:bool,
+
+    // Язык, наличие сохранения и область экрана при построении меню
+    language:String,
+    continue_game:bool,
+    area:[f32;4],
 }
 
 impl MainMenu{
-    pub fn new(window:&Window,graphics:&mut Graphics2D,images:&[RgbaImage])->MainMenu{
+    pub fn new(_window:&Window,graphics:&mut Graphics2D,context:&mut GameContext)->GameResult<MainMenu>{
         // Изменение картинки обоев
-        graphics.get_textured_object_texture(wallpaper_index).update(&images[0]);
+        let wallpaper_ready=MainMenu::update_wallpaper(context,graphics);
 
-        let leaf=Texture::from_image(&images[1],window.display()).unwrap();
-        let leaf=graphics.add_texture(leaf);
-        let leaf_image_base=ImageBase::new([0f32,-100f32,100f32,100f32],White);
-        let leaf=graphics.add_textured_object(&leaf_image_base,leaf).unwrap();
+        let (leaf_texture,leaf_uv)=context.atlas_image("leaf")?;
+        let leaves_settings=EmitterSettings::preset(ParticlePreset::Petals,unsafe{[window_width,window_height]})
+                .uv(leaf_uv)
+                .seed(leaves_seed);
+        let leaves=ParticleEmitter::new(leaves_settings,leaf_texture,graphics);
 
 
-        let mut buttons=Vec::with_capacity(4);
-        if unsafe{game_settings.continue_game}{
-            buttons.push("Продолжить");
-        }
-        buttons.push("Новая игра");
-        buttons.push("Настройки");
-        buttons.push("Выход");
-
-        let menu_settings=MenuSettings::new(game_name,buttons.into_iter())
-                .header_font_size(60f32)
-                .button_size([160f32,60f32])
-                .button_font_size(26f32);
-
-        let enter_name_rect=unsafe{[
-            window_center[0]-120f32,
-            window_center[1]-100f32,
-            240f32,
-            140f32,
-        ]};
-        let enter_name_settings=EditTextViewSettings::new("",enter_name_rect);
-
-        Self{
-            leaf,
-            leaves:Vec::with_capacity(10),
-            menu:Menu::new(menu_settings,graphics),
-            enter_name:false,
-            user_name:EditTextView::new(enter_name_settings,graphics),
+        let mut buttons=Vec::with_capacity(5);
+        unsafe{
+            if game_settings.continue_game{
+                buttons.push(localization.get("main_menu_continue"));
+            }
+            buttons.push(localization.get("main_menu_new_game"));
+            buttons.push(localization.get("main_menu_settings"));
+            buttons.push(localization.get("main_menu_gallery"));
+            buttons.push(localization.get("main_menu_exit"));
         }
-    }
 
-    pub fn open(&mut self,window:&mut Window,swipe_direction:SwipeDirection,graphics:&mut Graphics2D)->Game{
-        let mut result=Game::Next;
-
-        let mut frames=0u8;
-
-        let mut current_page_shift=[0f32;2];
-
-        let (
-            mut next_page_shift,
-            dshift
-        )=unsafe{
-            match swipe_direction{
-                SwipeDirection::Up=>(
-                    [
-                        0f32,
-                        window_height
-                    ],
-                    [
-                        0f32,
-                        -window_height/swipe_updates as f32
-                    ]
-                ),
-                SwipeDirection::Down=>(
-                    [
-                        0f32,
-                        -window_height
-                    ],
-                    [
-                        0f32,
-                        window_height/swipe_updates as f32
-                    ]
-                ),
-                SwipeDirection::Left=>(
-                    [
-                        window_width,
-                        0f32
-                    ],
-                    [
-                        -window_width/swipe_updates as f32,
-                        0f32
-                    ]
-                ),
-                SwipeDirection::Right=>(
-                    [
-                        -window_width,
-                        0f32
-                    ],
-                    [
-                        window_width/swipe_updates as f32,
-                        0f32
-                    ]
-                )
-            }
+        let area=unsafe{viewport.area()};
+
+        let menu_settings=unsafe{
+            MenuSettings::new(game_name,buttons.into_iter(),&theme)
+                .layout(area)
+                .header_size(viewport.size([100f32,60f32]))
+                .header_spacing(viewport.length(theme.header_spacing))
+                .header_font_size(viewport.length(60f32))
+                .button_size(viewport.size([160f32,60f32]))
+                .button_spacing(viewport.length(theme.button_spacing))
+                .button_font_size(viewport.length(26f32))
         };
 
-        window.run(|window,event|{
-            match event{
-                WindowEvent::CloseRequested=>result=Game::Exit,
-                WindowEvent::Update=>{
-                    frames+=1;
-                    if frames==swipe_updates{
-                        window.stop_events();
-                    }
-                    else{
-                        current_page_shift[0]+=dshift[0];
-                        current_page_shift[1]+=dshift[1];
+        let enter_name_rect=unsafe{viewport.anchored(Anchor::Center,[0f32,-30f32],[240f32,140f32])};
+        let enter_name_settings=EditTextViewSettings::new("",enter_name_rect,unsafe{&theme})
+                .font_size(unsafe{viewport.length(20f32)})
+                .capacity(max_name_length);
 
-                        next_page_shift[0]+=dshift[0];
-                        next_page_shift[1]+=dshift[1];
-                    }
-                }
-
-                WindowEvent::RedrawRequested=>{
-                    let [dx,dy]=unsafe{mouse_cursor.center_radius()};
+        let name_prompt_rect=unsafe{[
+            enter_name_rect[0],
+            enter_name_rect[1]-viewport.length(50f32),
+            enter_name_rect[2],
+            viewport.length(40f32),
+        ]};
+        let name_prompt_settings=TextViewSettings::new(
+            unsafe{localization.get("enter_name_prompt")},
+            GeneralSettings::new(name_prompt_rect),
+            unsafe{&theme}
+        )
+                .font_size(unsafe{viewport.length(24f32)})
+                .text_colour(unsafe{theme.label_colour});
+
+        Ok(Self{
+            leaves,
+            menu:Menu::new(menu_settings,graphics),
+            enter_name:false,
+            name_prompt:TextView::new(name_prompt_settings,graphics),
+            user_name:EditTextView::new(enter_name_settings,graphics),
 
-                    let wallpaper_shift=[
-                        dx/wallpaper_movement_scale+next_page_shift[0],
-                        dy/wallpaper_movement_scale+next_page_shift[1]
-                    ];
+            wallpaper_ready,
 
-                    let menu_shift=[
-                        dx/menu_movement_scale+next_page_shift[0],
-                        dy/menu_movement_scale+next_page_shift[1]
-                    ];
 
-                    let leaf_shift=[
-                        dx/leaf_movement_scale+next_page_shift[0],
-                        dy/leaf_movement_scale+next_page_shift[1]
-                    ];
+            language:unsafe{localization.language().to_string()},
+            continue_game:unsafe{game_settings.continue_game},
+            area,
+        })
+    }
 
-                    window.draw(&graphics,|graphics|{
-                        graphics.draw_shift_textured_object(swipe_screen_index,current_page_shift);
-                        graphics.draw_shift_textured_object(wallpaper_index,wallpaper_shift).unwrap();
+    fn wallpaper_key(context:&GameContext)->AssetKey{
+        AssetKey::wallpaper("main_menu",wallpaper_size(context.screen))
+    }
 
+    /// Замена картинки обоев, если она уже декодирована.
+    fn update_wallpaper(context:&mut GameContext,graphics:&mut Graphics2D)->bool{
+        match context.assets.get(&MainMenu::wallpaper_key(context)){
+            Some(wallpaper)=>{
+                context.wallpaper.texture(graphics).update(&wallpaper);
+                true
+            }
+            None=>false,
+        }
+    }
 
-                        for leaf in &self.leaves{
-                            let leaf=[
-                                leaf.x+leaf_shift[0],
-                                leaf.y+leaf_shift[1]
-                            ];
-                            graphics.draw_shift_textured_object(self.leaf,leaf).unwrap();
-                        }
+    /// Постановка в очередь изображений меню и первых страниц игры
+    /// (с сохранённой страницы при продолжении игры).
+    pub fn request_assets(context:&mut GameContext){
+        context.assets.request(&MainMenu::wallpaper_key(context));
+
+        let first_page=unsafe{if game_settings.continue_game{game_settings.saved_page}else{0usize}};
+        context.assets.prefetch_pages(
+            &context.page_table,
+            first_page,
+            prefetched_pages,
+            wallpaper_size(context.screen),
+            |dialogue|unsafe{localization.dialogue_path(&resources,dialogue)}
+        );
+    }
 
-                        self.menu.draw_shift(menu_shift,graphics);
-                    });
-                }
+    /// Построение главного меню.
+    pub fn open()->GameSceneAction{
+        SceneAction::replace(|context:&mut GameContext,window:&mut Window,graphics:&mut Graphics2D|{
+            page_or_error(MainMenu::new(window,graphics,context),context,window,graphics)
+        })
+    }
 
-                _=>{}
+    /// Вид курсора над полем ввода имени или кнопками меню.
+    fn cursor_state(&self,context:&GameContext)->CursorState{
+        if self.enter_name{
+            let [x,y]=unsafe{mouse_cursor.position()};
+            if self.user_name.in_area(x,y){
+                return CursorState::TextInput
+            }
+        }
+        else{
+            let [x,y]=context.cursor_in_layer(interface_layer);
+            if self.menu.button_at(x,y).is_some(){
+                return CursorState::Hover
             }
-        });
+        }
 
-        result
+        CursorState::Default
     }
 
-    pub fn run(&mut self,window:&mut Window,graphics:&mut Graphics2D,audio:&AudioWrapper)->Game{
-        let mut result=Game::Next;
+    /// Отрисовка обоев, лепестков и меню со сдвигом страницы.
+    fn render<S:Surface>(&self,shift:[f32;2],context:&GameContext,graphics:&mut Graphics<S>){
+        let parallax=&context.parallax;
 
-        let mut frames=0u16;
-        let mut spawn_time=0usize;
-        let mut spawn_position=0usize;
+        // Отрисовка обоев
+        if self.wallpaper_ready{
+            context.wallpaper.draw_shift(parallax.shift_with(wallpaper_layer,shift),graphics);
+        }
 
-        window.run(|window,event|{
-            match event{
-                WindowEvent::CloseRequested=>result=Game::Exit,
+        self.leaves.draw_shift(parallax.shift_with(particle_layer,shift),graphics);
 
-                WindowEvent::Update=>{
-                    frames+=1;
+        // Отрисовка меню
+        self.menu.draw_shift(parallax.shift_with(interface_layer,shift),graphics);
+    }
+}
 
-                    if frames==leaf_spawn_times[spawn_time]{
-                        frames=0;
+impl Scene<GameContext> for MainMenu{
+    fn resume(&mut self,_context:&mut GameContext,_window:&mut Window,_graphics:&mut Graphics2D)->GameSceneAction{
+        let changed=unsafe{
+            self.language!=localization.language()
+                || self.continue_game!=game_settings.continue_game
+                || self.area!=viewport.area()
+        };
 
-                        spawn_time+=1;
+        if changed{
+            // Язык, прогресс или размер окна изменены - меню строится заново
+            MainMenu::open().with_transition(swipe(SwipeDirection::Right))
+        }
+        else{
+            SceneAction::None.with_transition(swipe(SwipeDirection::Right))
+        }
+    }
 
-                        if spawn_time==leaf_spawn_times.len(){
-                            spawn_time=0;
-                        }
+    fn resize(&mut self,_context:&mut GameContext,_window:&mut Window,_graphics:&mut Graphics2D)->GameSceneAction{
+        MainMenu::open()
+    }
 
-                        let x=leaf_spawn_positions[spawn_position]*unsafe{window_width};
+    fn update(&mut self,elapsed:f32,context:&mut GameContext,_window:&mut Window,graphics:&mut Graphics2D)->GameSceneAction{
+        if !self.wallpaper_ready{
+            self.wallpaper_ready=MainMenu::update_wallpaper(context,graphics);
+        }
 
-                        spawn_position+=1;
-                        if spawn_position==leaf_spawn_positions.len(){
-                            spawn_position=0;
-                        }
+        self.leaves.update(elapsed);
 
-                        self.leaves.push(Leaf::new(x));
-                    }
+        let cursor_state=self.cursor_state(context);
+        context.cursor.set_state(cursor_state);
 
-                    let mut c=0;
-                    while c<self.leaves.len(){
-                        let leaf=&mut self.leaves[c];
+        SceneAction::None
+    }
 
-                        if leaf.y>unsafe{window_height}+100f32{
-                            // Удаление упавших лепестков
-                            self.leaves.remove(c);
-                            continue
+    fn input(&mut self,event:WindowEvent,context:&mut GameContext,window:&mut Window,graphics:&mut Graphics2D)->GameSceneAction{
+        match event{
+            WindowEvent::MousePressed(button)=>{
+                if let MouseButton::Left=button{
+                    if self.enter_name{
+                        let [x,y]=unsafe{mouse_cursor.position()};
+                        if !self.user_name.in_area(x,y){
+                            self.enter_name=false;
                         }
-                        else{
-                            // Движение лепестков
-                            if leaf.left_side{
-                                leaf.x+=leaf.x_speed;
-                            }
-                            else{
-                                leaf.x-=leaf.x_speed;
-                            }
-
-                            leaf.y+=leaf.y_speed;
-
-                            if leaf.x_up{
-                                leaf.x_speed+=x_accelerate;
-                                if leaf.x_speed>=x_max_speed{
-                                    leaf.x_up=false;
-                                }
-                            }
-                            else{
-                                leaf.x_speed-=x_accelerate;
-
-                                if leaf.x_speed<=x_min_speed{
-                                    leaf.x_up=true;
-                                    leaf.left_side=!leaf.left_side;
-                                }
-                            }
-
-                            if leaf.y_up{
-                                leaf.y_speed+=y_accelerate;
-                                if leaf.y_speed>=y_max_speed{
-                                    leaf.y_up=false;
-                                }
-                            }
-                            else{
-                                leaf.y_speed-=y_accelerate;
-
-                                if leaf.y_speed<=y_min_speed{
-                                    leaf.y_up=true;
-                                }
-                            }
+                    }
+                    else{
+                        // Положение курсора относительно сдвинутого меню
+                        let [x,y]=context.cursor_in_layer(interface_layer);
+                        if let Some(button)=self.menu.pressed(x,y){
+                            context.play_track("button_pressed",1u32);
+                            // Изменение цвета кнопки
+                            self.menu.set_button_colour(button,unsafe{theme.button_colours.pressed},graphics);
                         }
-                        // Следующий лепесток
-                        c+=1;
                     }
                 }
+            }
 
-                WindowEvent::RedrawRequested=>{
-                    let [dx,dy]=unsafe{mouse_cursor.center_radius()};
-                    let wallpaper_shift=[
-                        dx/wallpaper_movement_scale,
-                        dy/wallpaper_movement_scale
-                    ];
-                    let menu_shift=[
-                        dx/menu_movement_scale,
-                        dy/menu_movement_scale
-                    ];
-
-                    let leaf_shift=[
-                        dx/leaf_movement_scale,
-                        dy/leaf_movement_scale
-                    ];
-
-                    window.draw(graphics,|graphics|{
-                        // Отрисовка обоев
-                        graphics.draw_shift_textured_object(wallpaper_index,wallpaper_shift).unwrap();
-
-                        for leaf in &self.leaves{
-                            let leaf=[
-                                leaf.x+leaf_shift[0],
-                                leaf.y+leaf_shift[1]
-                            ];
-                            graphics.draw_shift_textured_object(self.leaf,leaf).unwrap();
-                        }
+            WindowEvent::MouseReleased(button)=>{
+                if let MouseButton::Left=button{
+                    if !self.enter_name{
+                        if let Some(pressed_button)=self.menu.pressed_button(){
+                            // Положение курсора относительно сдвинутого меню
+                            let [x,y]=context.cursor_in_layer(interface_layer);
 
-                        // Отрисовка меню
-                        self.menu.draw_shift(menu_shift,graphics);
+                            // Изменение цвета кнопки
+                            self.menu.set_button_colour(pressed_button,unsafe{theme.button_colours.normal},graphics);
 
-                        if self.enter_name{
-                            self.user_name.draw(graphics);
-                        }
+                            if let Some(mut button)=self.menu.released(x,y){
+                                if !self.continue_game{
+                                    button+=1;
+                                }
 
-                        // Отрисовка курсора
-                        graphics.draw_shift_textured_object(mouse_cursor_icon_index,[dx,dy]).unwrap();
-                    }).unwrap();
-                }
+                                match button{
+                                    // Продолжить игру
+                                    // (страниц игры пока нет - меню открывается заново)
+                                    0=>return MainMenu::open().with_transition(swipe(SwipeDirection::Left)),
 
-                WindowEvent::MousePressed(button)=>{
-                    if let MouseButton::Left=button{
-                        let [mut x,mut y]=unsafe{mouse_cursor.position()};
+                                    // Начать новую игру
+                                    1=>{
+                                        // Открытие диалога для ввода имени пользователя
+                                        self.name_prompt.set_text(unsafe{localization.get("enter_name_prompt")},graphics);
+                                        self.enter_name=true;
+                                    }
 
-                        if self.enter_name{
-                            if !self.user_name.in_area(x,y){
-                                self.enter_name=false;
-                            }
-                        }
-                        else{
-                            let [dx,dy]=unsafe{mouse_cursor.center_radius()};
-                            let menu_shift=[
-                                dx/menu_movement_scale,
-                                dy/menu_movement_scale
-                            ];
-
-                            x-=menu_shift[0];
-                            y-=menu_shift[1];
-                            if let Some(button)=self.menu.pressed(x,y){
-                                audio.play_track("button_pressed",1u32);
-                                // Получение индекса кнопки
-                                let button_index=self.menu.button_index(button);
-                                // Изменение цвета кнопки
-                                *graphics.get_simple_object_colour(button_index)=button_pressed;
-                            }
-                        }
-                    }
-                }
+                                    // Настройки
+                                    2=>return SceneAction::push(|_context:&mut GameContext,window:&mut Window,graphics:&mut Graphics2D|{
+                                        Box::new(Settings::new(window,graphics)) as Box<dyn Scene<GameContext>>
+                                    }).with_transition(swipe(SwipeDirection::Left)),
 
-                WindowEvent::MouseReleased(button)=>{
-                    if let MouseButton::Left=button{
-                        if !self.enter_name{
-                            if let Some(pressed_button)=self.menu.pressed_button(){
-                                // Текущее положение курсора
-                                let [mut x,mut y]=unsafe{mouse_cursor.position()};
-                                // Расстояние от курсора до центра экрана
-                                let [dx,dy]=unsafe{mouse_cursor.center_radius()};
-                                // Сдвиг меню
-                                let menu_shift=[
-                                    dx/menu_movement_scale,
-                                    dy/menu_movement_scale
-                                ];
-                                // Корректировка положения курсора
-                                // относительно сдвинутого меню
-                                x-=menu_shift[0];
-                                y-=menu_shift[1];
-
-                                // Получение индекса кнопки
-                                let button_index=self.menu.button_index(pressed_button);
-                                // Изменение цвета кнопки
-                                *graphics.get_simple_object_colour(button_index)=Light_blue;
-
-                                if let Some(mut button)=self.menu.released(x,y){
-                                    if unsafe{!game_settings.continue_game}{
-                                        button+=1;
-                                    }
+                                    // Снимки экрана
+                                    3=>return SceneAction::push(|_context:&mut GameContext,window:&mut Window,graphics:&mut Graphics2D|{
+                                        Box::new(Gallery::new(window,graphics,0usize)) as Box<dyn Scene<GameContext>>
+                                    }).with_transition(swipe(SwipeDirection::Left)),
 
-                                    match button{
-                                        // Продолжить игру
-                                        0=>{
-                                            window.stop_events();
-                                        }
-
-                                        // Начать новую игру
-                                        1=>{
-                                            // Открытие диалога для ввода имени пользователя
-                                            self.enter_name=true;
-                                        }
-
-                                        // Настройки
-                                        2=>{
-                                            self.render_to_texture(window,graphics);
-
-                                            match{
-                                                let mut settings=Settings::new(window,graphics);
-                                                settings.open(window,graphics);
-                                                settings.run(window,graphics,audio)
-                                            }{
-                                                Game::Exit=>{
-                                                    result=Game::Exit;
-                                                    window.stop_events();
-                                                }
-                                                _=>{
-                                                    self.open(window,SwipeDirection::Right,graphics);
-                                                }
-                                            }
-                                        }
-
-                                        // Выход
-                                        3=>{
-                                            window.stop_events();
-                                            result=Game::Exit;
-                                        }
-
-                                        _=>{}
-                                    }
+                                    // Выход
+                                    4=>return SceneAction::Exit,
+
+                                    _=>{}
                                 }
                             }
                         }
                     }
                 }
+            }
 
-                WindowEvent::CharacterInput(character)=>if self.enter_name{
-                    self.user_name.push_char(character,graphics);
-                }
+            WindowEvent::CharacterInput(character)=>if self.enter_name{
+                self.user_name.push_char(character,window.display(),graphics);
+            }
 
-                WindowEvent::KeyboardPressed(button)=>match button{
-                    KeyboardButton::Escape=>self.enter_name=false,
+            WindowEvent::KeyboardPressed(button)=>match button{
+                KeyboardButton::Escape=>self.enter_name=false,
 
-                    KeyboardButton::Backspace=>if self.enter_name{
-                        self.user_name.pop_char(graphics);
-                    }
+                KeyboardButton::Backspace=>if self.enter_name{
+                    self.user_name.pop_char(graphics);
+                }
 
-                    KeyboardButton::Enter=>if self.enter_name{
-                        unsafe{game_settings.user_name=self.user_name.text(graphics).clone()}
-                        window.stop_events();
+                KeyboardButton::Enter=>if self.enter_name{
+                    match validate_name(self.user_name.text(graphics),max_name_length){
+                        Ok(name)=>{
+                            unsafe{game_settings.user_name=name}
+                            save_settings();
+                            // Страниц игры пока нет - меню открывается заново
+                            return MainMenu::open().with_transition(swipe(SwipeDirection::Left))
+                        }
+                        Err(error)=>{
+                            let message=unsafe{
+                                match error{
+                                    NameError::Empty=>localization.get("name_empty"),
+                                    NameError::TooLong=>localization.get("name_too_long"),
+                                    NameError::InvalidCharacter=>localization.get("name_invalid"),
+                                }
+                            };
+                            self.name_prompt.set_text(message,graphics);
+                        }
                     }
-
-                    KeyboardButton::F5=>make_screenshot(window,audio),
-                    _=>{}
                 }
 
-                _=>{
-
-                }
+                _=>{}
             }
-        });
 
-        self.render_to_texture(window,graphics);
+            _=>{}
+        }
 
-        // Удаление всех простых объектов
-        graphics.remove_all_simple_objects();
-        // Удаление всех текстовых объектов
-        graphics.remove_all_text_objects();
-        result
+        SceneAction::None
     }
 
-    fn render_to_texture(&self,window:&Window,graphics:&mut Graphics2D){
-        // Расстояние от курсора до центра экрана
-        let [dx,dy]=unsafe{mouse_cursor.center_radius()};
-
-        let swipe_screen_texture=get_swipe_texture(graphics);
-
-        let wallpaper_shift=[
-            dx/wallpaper_movement_scale,
-            dy/wallpaper_movement_scale
-        ];
+    fn draw(&self,shift:[f32;2],context:&GameContext,graphics:&mut Graphics<Frame>){
+        self.render(shift,context,graphics);
 
-        let menu_shift=[
-            dx/menu_movement_scale,
-            dy/menu_movement_scale
-        ];
-
-        let leaf_shift=[
-            dx/leaf_movement_scale,
-            dy/leaf_movement_scale
-        ];
-
-        draw_on_texture(&swipe_screen_texture,window,graphics,|graphics|{
-            graphics.draw_shift_textured_object(wallpaper_index,wallpaper_shift).unwrap();
-
-            for leaf in &self.leaves{
-                let leaf=[
-                    leaf.x+leaf_shift[0],
-                    leaf.y+leaf_shift[1]
-                ];
-                graphics.draw_shift_textured_object(self.leaf,leaf).unwrap();
-            }
+        if self.enter_name{
+            self.name_prompt.draw(graphics);
+            self.user_name.draw(graphics);
+        }
+    }
 
-            // Отрисовка меню
-            self.menu.draw_shift(menu_shift,graphics);
-        });
+    fn draw_on_texture(&self,context:&GameContext,graphics:&mut Graphics<SimpleFrameBuffer>){
+        self.render([0f32;2],context,graphics)
     }
-}
\ No newline at end of file
+}
@@ -1,5 +1,5 @@
 mod loading_screen;
-pub use loading_screen::LoadingScreen;
+pub use loading_screen::{LoadingScreen,loading_screen_frames};
 
 mod main_menu;
 pub use main_menu::MainMenu;
@@ -7,20 +7,42 @@ pub use main_menu::MainMenu;
 mod settings;
 pub use settings::Settings;
 
-use lib::colours::Light_blue;
+mod gallery;
+pub use gallery::Gallery;
 
-use cat_engine::Colour;
+mod error_page;
+pub use error_page::ErrorPage;
 
-const button_pressed:Colour=[
-    Light_blue[0]-0.05,
-    Light_blue[1]-0.05,
-    Light_blue[2]-0.05,
-    Light_blue[3],
-];
+use crate::{
+    swipe_duration,
+    GameContext,
+};
 
-pub enum SwipeDirection{
-    Up,
-    Down,
-    Left,
-    Right,
-}
\ No newline at end of file
+use lib::{
+    error::GameResult,
+    scene::Scene,
+    transition::{Transition,TransitionEffect,SwipeDirection},
+};
+
+use cat_engine::{
+    Window,
+    graphics::Graphics2D,
+};
+
+/// Переход сдвигом страниц.
+fn swipe(direction:SwipeDirection)->Transition{
+    Transition::new(TransitionEffect::Slide(direction),swipe_duration)
+}
+
+/// Страница или страница ошибки, если страницу не удалось построить.
+pub fn page_or_error<P:Scene<GameContext>+'static>(
+    page:GameResult<P>,
+    context:&GameContext,
+    window:&Window,
+    graphics:&mut Graphics2D
+)->Box<dyn Scene<GameContext>>{
+    match page{
+        Ok(page)=>Box::new(page),
+        Err(error)=>Box::new(ErrorPage::new(window,graphics,context,&error)),
+    }
+}
@@ -1,227 +1,341 @@
 use crate::{
-    // consts
-    game_name,
-    mouse_cursor_icon_index,
-    wallpaper_index,
-    wallpaper_movement_scale,
-    swipe_updates,
-    swipe_screen_index,
     // statics
     game_settings,
-    // enums
-    Game,
-    // functions
-    get_swipe_texture,
-    draw_on_texture,
-    make_screenshot,
+    localization,
+    viewport,
+    theme,
+    // structs
+    GameContext,
+    // types
+    GameSceneAction,
+    // fns
+    set_fullscreen,
+    save_settings,
 };
 
-use super::button_pressed;
-
 use lib::{
-    colours::{White,Gray,Dark_gray,Light_blue},
+    AlignY,
+    scene::{Scene,SceneAction},
+    cursor::CursorState,
+    viewport::Anchor,
     user_interface::{
         Button,
         ButtonSettings,
+        GeneralSettings,
+        VStack,
+        Padding,
     },
 };
 
 use cat_engine::{
-    // types
-    Colour,
     // statics
     mouse_cursor,
-    window_center,
-    window_height,
-    window_width,
     // enums
     KeyboardButton,
     // structs
     Window,
     WindowEvent,
     MouseButton,
-    graphics::{Graphics2D,DependentObject},
-    texture::{ImageObject,ImageBase,Texture},
-    image::RgbaImage,
-    audio::AudioWrapper,
+    graphics::{Graphics,Graphics2D},
+
+    glium::{
+        Frame,
+        Surface,
+        framebuffer::SimpleFrameBuffer,
+    },
 };
 
-pub const page_colour:Colour=Dark_gray;
+/// Кнопки страницы настроек
+#[derive(Clone,Copy,PartialEq)]
+enum SettingsButton{
+    ResetGameProgress,
+    Language,
+    Fullscreen,
+    Parallax,
+    SystemCursor,
+    Escape,
+}
 
 pub struct Settings{
-    button_pressed:Option<usize>,
+    button_pressed:Option<SettingsButton>,
     reset_game_progress:Button,
+    language:Button,
+    fullscreen:Button,
+    parallax:Button,
+    system_cursor:Button,
     escape:Button,
 }
 
 impl Settings{
     pub fn new(_window:&Window,graphics:&mut Graphics2D)->Settings{
-        let escape_rect=unsafe{[
-            10f32,
-            window_height-70f32,
-            160f32,
-            60f32,
-        ]};
-        let escape_settings=ButtonSettings::new("Назад",escape_rect);
+        let font_size=unsafe{viewport.length(theme.button_font_size)};
+        let padding=unsafe{theme.page_padding};
+
+        let escape_rect=unsafe{viewport.anchored(Anchor::BottomLeft,[padding,-padding],[160f32,60f32])};
+        let escape_settings=ButtonSettings::new(unsafe{localization.get("settings_back")},escape_rect,unsafe{&theme})
+                .font_size(font_size);
         let escape=Button::new(escape_settings,graphics);
 
-        let reset_game_progress_rect=unsafe{[
-            window_center[0]-125f32,
-            70f32,
-            250f32,
-            60f32,
-        ]};
-        let reset_game_progress_settings=ButtonSettings::new("Сбросить прогресс игры",reset_game_progress_rect);
+        // Кнопки настроек - столбцом по центру сверху
+        let button_size=unsafe{viewport.size([250f32,60f32])};
+        let rects=unsafe{
+            VStack::new(GeneralSettings::new(viewport.area()))
+                .padding(Padding::new(0f32,viewport.length(70f32),0f32,0f32))
+                .spacing(viewport.length(theme.button_spacing))
+                .align_y(AlignY::Up)
+                .rects(&[button_size;5])
+        };
+
+        let reset_game_progress_rect=rects[0];
+        let reset_game_progress_settings=ButtonSettings::new(unsafe{localization.get("settings_reset_progress")},reset_game_progress_rect,unsafe{&theme})
+                .font_size(font_size);
         let reset_game_progress=Button::new(reset_game_progress_settings,graphics);
 
+        let language_rect=rects[1];
+        let language_settings=ButtonSettings::new(language_label(),language_rect,unsafe{&theme})
+                .font_size(font_size);
+        let language=Button::new(language_settings,graphics);
+
+        let fullscreen_rect=rects[2];
+        let fullscreen_settings=ButtonSettings::new(fullscreen_label(),fullscreen_rect,unsafe{&theme})
+                .font_size(font_size);
+        let fullscreen=Button::new(fullscreen_settings,graphics);
+
+        let parallax_rect=rects[3];
+        let parallax_settings=ButtonSettings::new(parallax_label(),parallax_rect,unsafe{&theme})
+                .font_size(font_size);
+        let parallax=Button::new(parallax_settings,graphics);
+
+        let system_cursor_rect=rects[4];
+        let system_cursor_settings=ButtonSettings::new(system_cursor_label(),system_cursor_rect,unsafe{&theme})
+                .font_size(font_size);
+        let system_cursor=Button::new(system_cursor_settings,graphics);
+
         Self{
             button_pressed:None,
             reset_game_progress,
+            language,
+            fullscreen,
+            parallax,
+            system_cursor,
             escape,
         }
     }
 
-    pub fn open(&mut self,window:&mut Window,graphics:&mut Graphics2D)->Game{
-        let mut result=Game::Next;
-
-        let mut frames=0u8;
-
-        let mut shift=0f32;
-
-        let dshift=unsafe{window_width/swipe_updates as f32};
-
-        window.run(|window,event|{
-            match event{
-                WindowEvent::CloseRequested=>result=Game::Exit,
-                WindowEvent::Update=>{
-                    frames+=1;
-                    if frames==swipe_updates{
-                        window.stop_events();
-                    }
-                    else{
-                        shift-=dshift;
-                    }
-                }
+    /// Обновление подписей после смены языка.
+    fn update_labels(&self,graphics:&mut Graphics2D){
+        unsafe{
+            self.escape.set_text(localization.get("settings_back"),graphics);
+            self.reset_game_progress.set_text(localization.get("settings_reset_progress"),graphics);
+        }
+        self.language.set_text(language_label(),graphics);
+        self.fullscreen.set_text(fullscreen_label(),graphics);
+        self.parallax.set_text(parallax_label(),graphics);
+        self.system_cursor.set_text(system_cursor_label(),graphics);
+    }
 
-                WindowEvent::RedrawRequested=>{
-                    let next_page_shift=unsafe{window_width+shift};
+    fn button(&self,button:SettingsButton)->&Button{
+        match button{
+            SettingsButton::ResetGameProgress=>&self.reset_game_progress,
+            SettingsButton::Language=>&self.language,
+            SettingsButton::Fullscreen=>&self.fullscreen,
+            SettingsButton::Parallax=>&self.parallax,
+            SettingsButton::SystemCursor=>&self.system_cursor,
+            SettingsButton::Escape=>&self.escape,
+        }
+    }
 
-                    window.draw(&graphics,|graphics|{
-                        graphics.clear_colour(page_colour);
+    /// Кнопка под курсором.
+    fn hovered_button(&self)->Option<SettingsButton>{
+        let [x,y]=unsafe{mouse_cursor.position()};
+        [
+            SettingsButton::ResetGameProgress,
+            SettingsButton::Language,
+            SettingsButton::Fullscreen,
+            SettingsButton::Parallax,
+            SettingsButton::SystemCursor,
+            SettingsButton::Escape,
+        ].iter().copied().find(|&button|self.button(button).in_area(x,y))
+    }
 
-                        graphics.draw_shift_textured_object(swipe_screen_index,[shift,0f32]);
+    fn render<S:Surface>(&self,shift:[f32;2],graphics:&mut Graphics<S>){
+        self.reset_game_progress.draw_shift(shift,graphics);
+        self.language.draw_shift(shift,graphics);
+        self.fullscreen.draw_shift(shift,graphics);
+        self.parallax.draw_shift(shift,graphics);
+        self.system_cursor.draw_shift(shift,graphics);
+        self.escape.draw_shift(shift,graphics);
+    }
+}
 
-                        self.reset_game_progress.draw_shift([next_page_shift,0f32],graphics);
-                        self.escape.draw_shift([next_page_shift,0f32],graphics);
-                    });
-                }
+impl Scene<GameContext> for Settings{
+    fn resize(&mut self,_context:&mut GameContext,_window:&mut Window,_graphics:&mut Graphics2D)->GameSceneAction{
+        // Страница строится заново под новый размер окна
+        SceneAction::replace(|_context:&mut GameContext,window:&mut Window,graphics:&mut Graphics2D|{
+            Box::new(Settings::new(window,graphics)) as Box<dyn Scene<GameContext>>
+        })
+    }
 
-                _=>{}
-            }
-        });
+    fn update(&mut self,_elapsed:f32,context:&mut GameContext,_window:&mut Window,_graphics:&mut Graphics2D)->GameSceneAction{
+        let cursor_state=if self.hovered_button().is_some(){CursorState::Hover}else{CursorState::Default};
+        context.cursor.set_state(cursor_state);
 
-        result
+        SceneAction::None
     }
 
-    pub fn run(&mut self,window:&mut Window,graphics:&mut Graphics2D,audio:&AudioWrapper)->Game{
-        let mut result=Game::Next;
+    fn input(&mut self,event:WindowEvent,context:&mut GameContext,window:&mut Window,graphics:&mut Graphics2D)->GameSceneAction{
+        match event{
+            WindowEvent::MousePressed(button)=>{
+                if let MouseButton::Left=button{
+                    let [x,y]=unsafe{mouse_cursor.position()};
 
-        window.run(|window,event|{
-            match event{
-                WindowEvent::CloseRequested=>result=Game::Exit,
+                    self.button_pressed=None;
 
-                WindowEvent::RedrawRequested=>{
-                    let [dx,dy]=unsafe{mouse_cursor.center_radius()};
-                    window.draw(graphics,|graphics|{
-                        // Фон
-                        graphics.clear_colour(page_colour);
-
-                        self.reset_game_progress.draw(graphics);
-                        self.escape.draw(graphics);
+                    if self.reset_game_progress.pressed(x,y){
+                        self.button_pressed=Some(SettingsButton::ResetGameProgress);
+                    }
+                    else if self.language.pressed(x,y){
+                        self.button_pressed=Some(SettingsButton::Language);
+                    }
+                    else if self.fullscreen.pressed(x,y){
+                        self.button_pressed=Some(SettingsButton::Fullscreen);
+                    }
+                    else if self.parallax.pressed(x,y){
+                        self.button_pressed=Some(SettingsButton::Parallax);
+                    }
+                    else if self.system_cursor.pressed(x,y){
+                        self.button_pressed=Some(SettingsButton::SystemCursor);
+                    }
+                    else if self.escape.pressed(x,y){
+                        self.button_pressed=Some(SettingsButton::Escape);
+                    }
 
-                        // Отрисовка курсора
-                        graphics.draw_shift_textured_object(mouse_cursor_icon_index,[dx,dy]).unwrap();
-                    }).unwrap();
+                    if let Some(button)=self.button_pressed{
+                        context.play_track("button_pressed",1u32);
+                        self.button(button).set_background_colour(unsafe{theme.button_colours.pressed},graphics);
+                    }
                 }
+            }
 
-                WindowEvent::MousePressed(button)=>{
-                    if let MouseButton::Left=button{
+            WindowEvent::MouseReleased(button)=>{
+                if let MouseButton::Left=button{
+                    if let Some(button)=self.button_pressed{
                         let [x,y]=unsafe{mouse_cursor.position()};
+                        self.button(button).set_background_colour(unsafe{theme.button_colours.normal},graphics);
 
-                        self.button_pressed=None;
-
-                        if self.reset_game_progress.pressed(x,y){
-                            audio.play_track("button_pressed",1u32);
-                            *graphics.get_simple_object_colour(self.reset_game_progress.background_index())=button_pressed;
-                            self.button_pressed=Some(self.reset_game_progress.background_index());
-                        }
-                        else if self.escape.pressed(x,y){
-                            audio.play_track("button_pressed",1u32);
-                            *graphics.get_simple_object_colour(self.escape.background_index())=button_pressed;
-                            self.button_pressed=Some(self.escape.background_index());
+                        if button==SettingsButton::Escape{
+                            if self.escape.released(x,y){
+                                // escape action
+                                return SceneAction::Pop
+                            }
                         }
-                    }
-                }
-
-                WindowEvent::MouseReleased(button)=>{
-                    if let MouseButton::Left=button{
-                        if let Some(button)=self.button_pressed{
-                            let [x,y]=unsafe{mouse_cursor.position()};
-                            *graphics.get_simple_object_colour(button)=Light_blue;
-
-                            if button==self.escape.background_index(){
-                                if self.escape.released(x,y){
-                                    // escape action
-                                    window.stop_events();
+                        else if button==SettingsButton::Language{
+                            if self.language.released(x,y){
+                                // Переключение на следующий язык
+                                unsafe{
+                                    if localization.next_language().is_ok(){
+                                        game_settings.language=localization.language().to_string();
+                                    }
                                 }
+                                save_settings();
+                                self.update_labels(graphics);
                             }
-                            else{
-                                if self.reset_game_progress.released(x,y){
-                                    // reset action
-                                    unsafe{
-                                        game_settings.continue_game=false;
-                                    }
+                        }
+                        else if button==SettingsButton::Fullscreen{
+                            if self.fullscreen.released(x,y){
+                                // Переключение режима окна
+                                let fullscreen=unsafe{
+                                    game_settings.fullscreen=!game_settings.fullscreen;
+                                    game_settings.fullscreen
+                                };
+                                set_fullscreen(window,fullscreen,context.screen);
+                                save_settings();
+                                self.fullscreen.set_text(fullscreen_label(),graphics);
+                            }
+                        }
+                        else if button==SettingsButton::Parallax{
+                            if self.parallax.released(x,y){
+                                // Включение и выключение сдвига слоёв за курсором
+                                let parallax=unsafe{
+                                    game_settings.parallax=!game_settings.parallax;
+                                    game_settings.parallax
+                                };
+                                context.parallax.set_enabled(parallax);
+                                save_settings();
+                                self.parallax.set_text(parallax_label(),graphics);
+                            }
+                        }
+                        else if button==SettingsButton::SystemCursor{
+                            if self.system_cursor.released(x,y){
+                                // Переключение системного курсора и курсора игры
+                                let system_cursor=unsafe{
+                                    game_settings.system_cursor=!game_settings.system_cursor;
+                                    game_settings.system_cursor
+                                };
+                                context.cursor.set_system(system_cursor,window);
+                                save_settings();
+                                self.system_cursor.set_text(system_cursor_label(),graphics);
+                            }
+                        }
+                        else{
+                            if self.reset_game_progress.released(x,y){
+                                // reset action
+                                unsafe{
+                                    game_settings.continue_game=false;
                                 }
+                                save_settings();
                             }
                         }
                     }
                 }
+            }
 
-                WindowEvent::KeyboardPressed(button)=>match button{
-                    KeyboardButton::Escape=>{
-                        window.stop_events();
-                    }
-
-                    KeyboardButton::F5=>make_screenshot(window,audio),
+            WindowEvent::KeyboardPressed(KeyboardButton::Escape)=>return SceneAction::Pop,
 
-                    _=>{}
-                }
+            _=>{}
+        }
 
-                _=>{
+        SceneAction::None
+    }
 
-                }
-            }
-        });
+    fn draw(&self,shift:[f32;2],_context:&GameContext,graphics:&mut Graphics<Frame>){
+        graphics.clear_colour(unsafe{theme.page_colour});
+        self.render(shift,graphics);
+    }
 
-        self.render_to_texture(window,graphics);
+    fn draw_on_texture(&self,_context:&GameContext,graphics:&mut Graphics<SimpleFrameBuffer>){
+        graphics.clear_colour(unsafe{theme.page_colour});
+        self.render([0f32;2],graphics);
+    }
+}
 
-        // Удаление всех простых объектов
-        graphics.remove_last_simple_object();
-        graphics.remove_last_simple_object();
-        // Удаление всех текстовых объектов
-        graphics.remove_last_text_object();
-        graphics.remove_last_text_object();
-        result
+/// Подпись кнопки выбора языка.
+fn language_label()->String{
+    unsafe{
+        format!("{}: {}",localization.get("settings_language"),localization.get("language_name"))
     }
+}
 
-    fn render_to_texture(&self,window:&Window,graphics:&mut Graphics2D){
-        let swipe_screen_texture=get_swipe_texture(graphics);
+/// Подпись кнопки режима окна.
+fn fullscreen_label()->String{
+    unsafe{
+        let state=if game_settings.fullscreen{"settings_on"}else{"settings_off"};
+        format!("{}: {}",localization.get("settings_fullscreen"),localization.get(state))
+    }
+}
 
-        draw_on_texture(&swipe_screen_texture,window,graphics,|graphics|{
-            graphics.clear_colour(page_colour);
+/// Подпись кнопки параллакса.
+fn parallax_label()->String{
+    unsafe{
+        let state=if game_settings.parallax{"settings_on"}else{"settings_off"};
+        format!("{}: {}",localization.get("settings_parallax"),localization.get(state))
+    }
+}
 
-            self.reset_game_progress.draw(graphics);
-            self.escape.draw(graphics);
-        });
+/// Подпись кнопки системного курсора.
+fn system_cursor_label()->String{
+    unsafe{
+        let state=if game_settings.system_cursor{"settings_on"}else{"settings_off"};
+        format!("{}: {}",localization.get("settings_system_cursor"),localization.get(state))
     }
 }
\ No newline at end of file